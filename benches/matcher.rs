@@ -0,0 +1,88 @@
+//! Throughput benchmarks for the matching hot path.
+//!
+//! Run with `cargo bench`. The size of the generated corpus (in MiB)
+//! can be changed with the `LENZ_BENCH_MB` environment variable.
+
+
+use std::env;
+use std::hint::black_box;
+use std::time::{ Duration, Instant, };
+use lenz::{ count_query, mmap, };
+use lenz::matcher::{ Matcher, };
+
+
+/// The default size of the corpus in MiB.
+const CORPUS_MB: usize = 64;
+/// Every run is repeated this many times and the best time is reported.
+const ROUNDS: usize = 3;
+
+
+fn main() {
+    let mb: usize = env::var("LENZ_BENCH_MB").ok()
+                                             .and_then(|v| v.parse().ok())
+                                             .unwrap_or(CORPUS_MB);
+    let corpus = gen_corpus(mb * 1024 * 1024);
+    let lines: Vec<&str> = corpus.lines().collect();
+    println!("corpus: {} MiB, {} lines\n", mb, lines.len());
+
+    for ignore_case in [false, true] {
+        let q = "timeout";
+        bench(&format!("count_query per line     (ignore_case={})", ignore_case), corpus.len(), || {
+            lines.iter().filter_map(|l| count_query(q, l, ignore_case)).sum()
+        });
+        let matcher = Matcher::new(q, ignore_case);
+        bench(&format!("Matcher::count per line  (ignore_case={})", ignore_case), corpus.len(), || {
+            lines.iter().filter_map(|l| matcher.count(l)).sum()
+        });
+        bench(&format!("mmap::count whole buffer (ignore_case={})", ignore_case), corpus.len(), || {
+            mmap::count(corpus.as_bytes(), &matcher)
+        });
+        bench(&format!("mmap::matched_lines      (ignore_case={})", ignore_case), corpus.len(), || {
+            mmap::matched_lines(corpus.as_bytes(), &matcher).count()
+        });
+        println!();
+    }
+}
+
+
+/// Runs `f` a few times and prints the throughput of the fastest round.
+fn bench<F: FnMut() -> usize>(name: &str, bytes: usize, mut f: F) {
+    let mut best = Duration::MAX;
+    let mut result = 0;
+    for _ in 0..ROUNDS {
+        let start = Instant::now();
+        result = black_box(f());
+        best = best.min(start.elapsed());
+    }
+    let mbps = bytes as f64 / (1024.0 * 1024.0) / best.as_secs_f64();
+    println!("{:<45} {:>10.1} MiB/s  ({} hits, {:?})", name, mbps, result, best);
+}
+
+
+/// Generates a log-like corpus of roughly `size` bytes where about
+/// one line in fifty contains the query.
+fn gen_corpus(size: usize) -> String {
+    let words = [
+        "request", "handled", "user", "session", "cache", "miss", "ok",
+        "latency", "upstream", "GET", "POST", "/api/v1/items", "200", "404",
+    ];
+    let mut corpus = String::with_capacity(size + 128);
+    let mut seed: u64 = 0x9E37_79B9_7F4A_7C15;
+    let mut n: usize = 0;
+    while corpus.len() < size {
+        corpus.push_str(&format!("2024-01-01T00:00:{:02} INFO ", n % 60));
+        for _ in 0..12 {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            corpus.push_str(words[(seed % words.len() as u64) as usize]);
+            corpus.push(' ');
+        }
+        if n.is_multiple_of(50) {
+            corpus.push_str(if n.is_multiple_of(100) { "Timeout" } else { "timeout" });
+        }
+        corpus.push('\n');
+        n += 1;
+    }
+    corpus
+}
@@ -4,118 +4,329 @@
 //! for better display of the output.
 
 
-use crate::{ count_query, };
-use crate::style::{hg_query, ANSIStyle};
+use std::ops::{ Range, };
+use crate::{ hyperlink, };
+use crate::matcher::{ Matcher, };
+use crate::style::{ hg_matches, hg_spans, Look, };
+use crate::theme::{ Slot, };
+use crate::width::{ self, column_at, padding, Cursor, };
 
 
 /// The space between the **line number** and the **formatted content** of the line.
-const VOID: usize = 7;
+pub const VOID: usize = 7;
+/// The fewest columns a wrapped line is given, however narrow the terminal is.
+const MIN_WRAP: usize = 10;
+
+
+/// What is displayed next to the line number of a `Cell`, separated by colons.
+#[derive(Clone, Copy, Default)]
+pub struct Gutter {
+    /// The column of the first match, counted in display columns from `1`.
+    /// Lines without a match (in page view) have no column.
+    pub column: bool,
+    /// The absolute byte offset of the first match (or of the line, if it has no match).
+    pub byte_offset: bool,
+}
+
+
+/// How a long line is fitted into the terminal.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Fit {
+    /// The line is displayed as it is.
+    #[default]
+    Full,
+    /// At most this many columns of the line are displayed, around its first match (`--max-columns N`).
+    Truncate(usize),
+    /// The line is wrapped to a terminal of this many columns, under the start of the line (`--wrap`).
+    Wrap(usize),
+}
 
 
 /// A `Cell` is a **structure** for displaying **line-by-line** matches in a file.
-pub struct Cell {
-    query: String,
+pub struct Cell<'a> {
+    matcher: &'a Matcher,
+    look: &'a Look,
     line: String,
     lineno: usize,
+    offset: u64,
     start_at: usize,
 }
 
 
-impl Cell {
+impl<'a> Cell<'a> {
     /// `Cell` Constructor.
-    pub fn new(query: String, line: String, lineno: usize, start_at: usize) -> Self {
-        Self { query, line, lineno , start_at}
+    /// The `offset` is the absolute byte offset of the start of the line in its file,
+    /// and the `look` decides how the `Cell` is styled when it is displayed.
+    pub fn new(matcher: &'a Matcher, look: &'a Look, line: String, lineno: usize, offset: u64, start_at: usize) -> Self {
+        Self { matcher, look, line, lineno, offset, start_at }
+    }
+
+    /// `line` attr getter.
+    pub fn line(&self) -> &str {
+        &self.line
+    }
+
+    /// `lineno` attr getter.
+    pub fn lineno(&self) -> usize {
+        self.lineno
     }
 
-    /// The task of this method is to display and print the contents of the matching `Cell`.
+    /// `offset` attr getter.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// This method returns the `(start, len)` of each match in the line, in bytes.
+    pub fn spans(&self) -> Vec<(usize, usize)> {
+        self.matcher.find_iter(&self.line).collect()
+    }
+
+    /// This method returns the display column of a byte of the line, counted from `1`.
+    pub fn column(&self, byte: usize) -> usize {
+        column_at(&self.line, byte, self.look.tab_width)
+    }
+
+    /// The task of this method is to format the contents of the matching `Cell` for display.
     ///
     /// # Arguments
     ///
     /// * `lineno_color` - Dynamic mode to color matching line numbers.
-    /// * `ignore-case` - A boolean indicating whether the search should be case-insensitive.
-    pub fn print(&self, lineno_color: Option<bool>, ignore_case: bool) {
+    /// * `gutter` - What to display next to the line number.
+    /// * `path` - The path of the file, to link the line number to (if hyperlinks are on).
+    /// * `fit` - How a long line is fitted into the terminal.
+    ///
+    /// # Returns
+    ///
+    /// A `String` containing the styled row, without a line break
+    /// (apart from the ones between the parts of a wrapped line).
+    pub fn to_text(&self, lineno_color: Option<bool>, gutter: Gutter, path: Option<&str>, fit: Fit) -> String {
         let s = " ".repeat(self.start_at);
-        let void = " ".repeat(VOID - self.lineno.to_string().len());
-        let lineno;
-        let ig;
-        if ignore_case {
-            ig = true;
-        } else { ig = false;}
-        if let Some(true) = lineno_color {
-            lineno = self.get_formatted_lineno(true, ig);
-        } else {
-            lineno = self.get_formatted_lineno(false, ig);
-        }
-        println!(
+        let first = self.matcher.find_iter(&self.line).next();
+        let position = self.position_at(first.map(|(x, _)| x), gutter);
+        let void = padding(&position, VOID);
+        let lineno = self.get_formatted_lineno(lineno_color == Some(true), &position, path.map(|p| (p, first.map(|(x, _)| x))));
+        let line = match fit {
+            Fit::Full => self.get_formatted_line(),
+            Fit::Truncate(max) => self.get_truncated_line(max, first.unwrap_or((0, 0))),
+            Fit::Wrap(width) => {
+                let indent = self.indent(gutter);
+                self.get_wrapped_line(width.saturating_sub(indent).max(MIN_WRAP), indent)
+            },
+        };
+        format!(
             "{}{} {}{}",
             s,
             lineno,
             void,
-            self.get_formatted_line(ig),
+            line,
         )
     }
 
+    /// This method returns the number of columns before the line in the formatted row,
+    /// which is where the line starts on the screen.
+    pub fn indent(&self, gutter: Gutter) -> usize {
+        let first = self.matcher.find_iter(&self.line).next();
+        self.start_at + width::str_width(&self.position_at(first.map(|(x, _)| x), gutter)).max(VOID) + 1
+    }
+
     /// This method returns the status of a row relative to the query.
     /// `true` if it exists, `false` otherwise.
     ///
+    /// # Returns
+    ///
+    /// A boolean value, associated with a match or not match.
+    pub fn check_line(&self) -> bool {
+        self.matcher.is_match(&self.line)
+    }
+
+    /// This method returns the number of matches in the `line` of the `Cell`.
+    pub fn count_matches(&self) -> usize {
+        self.matcher.count(&self.line).unwrap_or(0)
+    }
+
+    /// This method returns the position of the `Cell` as it is displayed in the gutter:
+    /// the line number, then the column and the byte offset if they are asked for.
+    ///
     /// # Arguments
     ///
-    /// * `ignore-case` - A boolean indicating whether the search should be case-insensitive.
+    /// * `gutter` - What to display next to the line number.
     ///
     /// # Returns
     ///
-    /// A boolean value, associated with a match or not match.
-    pub fn check_line(&self, ignore_case: bool) -> bool {
-        if let Some(_) = count_query(&self.query, &self.line, ignore_case) {
-            return true;
+    /// A `String` like `12`, `12:5` or `12:5:3071`.
+    pub fn get_position(&self, gutter: Gutter) -> String {
+        let first = self.matcher.find_iter(&self.line).next().map(|(x, _)| x);
+        self.position_at(first, gutter)
+    }
+
+    /// The task of this method is to format each match of the `Cell` as its own row,
+    /// with the line number and the column of the match, instead of the whole line.
+    ///
+    /// # Arguments
+    ///
+    /// * `gutter` - What to display next to the line number. The column is always displayed.
+    /// * `path` - The path of the file, to link the line numbers to (if hyperlinks are on).
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<String>` containing one styled row per match.
+    pub fn to_text_only_matching(&self, gutter: Gutter, path: Option<&str>) -> Vec<String> {
+        let s = " ".repeat(self.start_at);
+        let gutter = Gutter { column: true, ..gutter };
+        self.matcher.find_iter(&self.line).map(|(x, n)| {
+            let position = self.position_at(Some(x), gutter);
+            let void = padding(&position, VOID);
+            format!(
+                "{}{} {}{}",
+                s,
+                self.get_formatted_lineno(false, &position, path.map(|p| (p, Some(x)))),
+                void,
+                self.look.paint(Slot::Match, self.span(x, n)),
+            )
+        }).collect()
+    }
+
+    /// This method returns the position of a match as it is displayed in the gutter.
+    ///
+    /// # Arguments
+    ///
+    /// * `at` - The byte index of the match in the line, if there is one.
+    /// * `gutter` - What to display next to the line number.
+    fn position_at(&self, at: Option<usize>, gutter: Gutter) -> String {
+        let mut position = self.lineno.to_string();
+        if gutter.column && let Some(x) = at {
+            position.push_str(&format!(":{}", column_at(&self.line, x, self.look.tab_width)));
+        }
+        if gutter.byte_offset {
+            position.push_str(&format!(":{}", self.offset + at.unwrap_or(0) as u64));
         }
-        false
+        position
+    }
+
+    /// This method returns the text of a match, which the `Matcher` always gives on character boundaries of the line.
+    fn span(&self, x: usize, n: usize) -> &str {
+        &self.line[x..x + n]
     }
 
     /// This method formats the line corresponding to `Cell`
     /// and returns it as a new string.
     ///
+    /// # Returns
+    ///
+    /// A formatted `String`.
+    fn get_formatted_line(&self) -> String {
+        hg_matches(self.look, self.matcher, &self.line)
+    }
+
+    /// This method highlights a part of the line, cutting the matches that cross its ends.
+    ///
     /// # Arguments
     ///
-    /// * `ignore-case` - A boolean indicating whether the search should be case-insensitive.
+    /// * `range` - The part of the line, in bytes, on character boundaries.
+    fn highlight_range(&self, range: Range<usize>) -> String {
+        let spans = self.matcher.find_iter(&self.line).filter_map(|(x, n)| {
+            let (start, end) = (x.max(range.start), (x + n).min(range.end));
+            (start < end && self.line.is_char_boundary(start) && self.line.is_char_boundary(end))
+                .then(|| (start - range.start, end - start))
+        });
+        hg_spans(self.look, &self.line[range.clone()], spans)
+    }
+
+    /// The task of this method is to format at most `max` columns of the line, around a match,
+    /// with a `[+N chars]…` marker for the part cut at the start and a `…[+N chars]` marker for the end.
+    ///
+    /// # Arguments
+    ///
+    /// * `max` - The number of columns to display.
+    /// * `focus` - The `(start, len)` of the match to center on, in bytes.
     ///
     /// # Returns
     ///
     /// A formatted `String`.
-    fn get_formatted_line(&self, ignore_case: bool) -> String {
-        hg_query(&self.query, &self.line, ignore_case)
+    fn get_truncated_line(&self, max: usize, focus: (usize, usize)) -> String {
+        // The byte index and the width of each character.
+        let mut cursor = Cursor::new(self.look.tab_width);
+        let chars: Vec<(usize, usize)> = self.line.char_indices().map(|(i, c)| (i, cursor.advance(c))).collect();
+        if cursor.column() <= max {
+            return self.get_formatted_line();
+        }
+        let index = |byte: usize| chars.partition_point(|&(i, _)| i < byte);
+        let (mut start, mut end) = (index(focus.0), index(focus.0 + focus.1));
+        let mut used: usize = chars[start..end].iter().map(|&(_, cw)| cw).sum();
+        // Half of what is left goes before the match, and the rest after it.
+        let before = max.saturating_sub(used) / 2;
+        let mut left = 0;
+        while start > 0 && left + chars[start - 1].1 <= before && used + chars[start - 1].1 <= max {
+            start -= 1;
+            left += chars[start].1;
+            used += chars[start].1;
+        }
+        while end < chars.len() && used + chars[end].1 <= max {
+            used += chars[end].1;
+            end += 1;
+        }
+        while start > 0 && used + chars[start - 1].1 <= max {
+            start -= 1;
+            used += chars[start].1;
+        }
+        let byte = |at: usize| chars.get(at).map_or(self.line.len(), |&(i, _)| i);
+        let mut s = String::new();
+        if start > 0 {
+            s.push_str(&format!("[+{} chars]…", start));
+        }
+        s.push_str(&self.highlight_range(byte(start)..byte(end)));
+        if end < chars.len() {
+            s.push_str(&format!("…[+{} chars]", chars.len() - end));
+        }
+        s
+    }
+
+    /// The task of this method is to wrap the line into parts of at most `width` columns,
+    /// each part after the first being indented by `indent` spaces.
+    ///
+    /// # Returns
+    ///
+    /// A formatted `String`, with the parts separated by line breaks.
+    fn get_wrapped_line(&self, width: usize, indent: usize) -> String {
+        let mut parts = Vec::new();
+        let mut start = 0;
+        let mut cursor = Cursor::new(self.look.tab_width);
+        for (i, c) in self.line.char_indices() {
+            if cursor.advance(c) > 0 && cursor.column() > width && i > start {
+                parts.push(self.highlight_range(start..i));
+                start = i;
+                cursor = Cursor::new(self.look.tab_width);
+                cursor.advance(c);
+            }
+        }
+        parts.push(self.highlight_range(start..self.line.len()));
+        parts.join(&format!("\n{}", " ".repeat(indent)))
     }
 
     /// This method formats and colors the line number
     /// of the `Cell` and returns it as a new `String`.
-    ///
+    /// The colons of the position get the `separator` style of the theme.
     ///
     /// # Arguments
     ///
     /// * `dyn_color` - A boolean to dynamically change the line number color.
-    /// * `ignore-case` - A boolean indicating whether the search should be case-insensitive.
+    /// * `position` - The position of the `Cell`, starting with the line number.
+    /// * `link` - The path of the file and the byte index of the linked match, if the line number should be a hyperlink.
     ///
     /// # Returns
     ///
     /// A `String`, containing the formatted line number.
-    fn get_formatted_lineno(&self, dyn_color: bool, ignore_case: bool) -> String {
-        let color: &str;
-        if dyn_color {
-            if self.check_line(ignore_case) == true {
-                color = ANSIStyle::FGRed.as_str();
-            } else {
-                color = ANSIStyle::FGGreen.as_str();
-            }
-        } else {
-            color = ANSIStyle::FGGreen.as_str();
+    fn get_formatted_lineno(&self, dyn_color: bool, position: &str, link: Option<(&str, Option<usize>)>) -> String {
+        let slot = if dyn_color && self.check_line() { Slot::MatchedLineno } else { Slot::Lineno };
+        let separator = self.look.paint(Slot::Separator, ":");
+        let parts: Vec<String> = position.split(':').map(|part| self.look.paint(slot, part)).collect();
+        let lineno = parts.join(&separator);
+        match link {
+            Some((path, at)) => {
+                let col = at.map_or(1, |x| column_at(&self.line, x, self.look.tab_width));
+                hyperlink::line(&self.look.hyperlink_format, &self.look.paths, path, self.lineno, col, &lineno)
+            },
+            None => lineno,
         }
-        String::from(
-            format!(
-                "{}{}{}",
-                color,
-                self.lineno,
-                ANSIStyle::Reset.as_str(),
-            )
-        )
     }
 }
\ No newline at end of file
@@ -0,0 +1,389 @@
+//! This module is related to the **config files** of `lenz`, which hold the defaults
+//! that would otherwise have to be typed as options every time.
+//!
+//! The user's config file is `$XDG_CONFIG_HOME/lenz/config.toml` (or `~/.config/lenz/config.toml`),
+//! and the project's config file is the nearest `.lenz.toml`, walked up from the working directory.
+//! The project's settings win over the user's, and the options on the command line win over both.
+//! If `LENZ_CONFIG` is set, only the file it names is read (an empty value reads no file).
+//!
+//! The files are written in a small subset of TOML: `key = value` lines and `#` comments,
+//! where a value is a string, a whole number, `true`/`false` or an array of strings.
+
+
+use std::collections::{ HashMap, };
+use std::env;
+use std::fs;
+use std::path::{ Path, PathBuf, };
+use crate::json;
+use crate::errors::{ LenzError, };
+use crate::opts::{ Options, };
+use crate::width::{ padding, str_width, };
+
+
+/// The name of the project's config file.
+pub const PROJECT_FILE: &str = ".lenz.toml";
+
+
+/// What a setting accepts.
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    /// `true` or `false`.
+    Flag,
+    /// A string or a whole number.
+    Value,
+    /// An array of strings (or a single string).
+    List,
+}
+
+
+/// The settings that can be written in a config file, in the order `--print-config` shows them.
+/// Apart from `case`, each one is the long option of the same name.
+const KEYS: [(&str, Kind); 19] = [
+    ("case", Kind::Value),
+    ("format", Kind::Value),
+    ("color", Kind::Value),
+    ("theme", Kind::Value),
+    ("colors", Kind::List),
+    ("hyperlink-format", Kind::Value),
+    ("exclude", Kind::List),
+    ("context", Kind::Value),
+    ("columns", Kind::Value),
+    ("max-count", Kind::Value),
+    ("max-total", Kind::Value),
+    ("max-columns", Kind::Value),
+    ("wrap", Kind::Flag),
+    ("tab-width", Kind::Value),
+    ("column", Kind::Flag),
+    ("byte-offset", Kind::Flag),
+    ("only-matching", Kind::Flag),
+    ("null", Kind::Flag),
+    ("mmap", Kind::Flag),
+];
+
+
+/// The value of a setting.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Bool(bool),
+    Int(u64),
+    Str(String),
+    List(Vec<String>),
+}
+
+
+/// A setting read from a config file, along with where it was found.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Setting {
+    pub key: &'static str,
+    pub value: Value,
+    pub path: String,
+    pub line: usize,
+}
+
+
+impl Setting {
+    /// This method returns where the setting was found, like `.lenz.toml:3`.
+    pub fn source(&self) -> String {
+        format!("{}:{}", self.path, self.line)
+    }
+
+    /// This method returns an error about the setting, pointing at its line.
+    pub fn error(&self, reason: &str) -> LenzError {
+        LenzError::Config { path: self.path.to_string(), line: self.line, reason: reason.to_string() }
+    }
+
+    /// The task of this method is to turn the setting into the options that have the same effect.
+    ///
+    /// # Returns
+    ///
+    /// The options, like `--context=2`. A flag that is `false` is left out.
+    ///
+    /// # Errors
+    ///
+    /// If `case` is not `sensitive`, `insensitive` or `smart`.
+    pub fn to_args(&self) -> Result<Vec<String>, LenzError> {
+        let option = format!("--{}", self.key);
+        if self.key == "case" {
+            let flag = match &self.value {
+                Value::Str(case) if case == "sensitive" => "-s",
+                Value::Str(case) if case == "insensitive" => "-i",
+                Value::Str(case) if case == "smart" => "-S",
+                _ => return Err(self.error("The value of 'case' should be \"sensitive\", \"insensitive\" or \"smart\".")),
+            };
+            return Ok(vec![flag.to_string()]);
+        }
+        Ok(match &self.value {
+            Value::Bool(true) => vec![option],
+            Value::Bool(false) if self.key == "mmap" => vec![String::from("--no-mmap")],
+            Value::Bool(false) => Vec::new(),
+            Value::Int(n) => vec![format!("{}={}", option, n)],
+            Value::Str(s) => vec![format!("{}={}", option, s)],
+            Value::List(items) => items.iter().map(|item| format!("{}={}", option, item)).collect(),
+        })
+    }
+}
+
+
+/// The task of this function is to find the config files, in the order their settings are applied.
+///
+/// # Returns
+///
+/// The paths of the config files that exist (or the one named by `LENZ_CONFIG`).
+pub fn discover() -> Vec<PathBuf> {
+    if let Some(path) = env::var_os("LENZ_CONFIG") {
+        return if path.is_empty() { Vec::new() } else { vec![PathBuf::from(path)] };
+    }
+    let mut paths = Vec::new();
+    let user_dir = env::var_os("XDG_CONFIG_HOME").filter(|d| !d.is_empty()).map(PathBuf::from)
+                                                  .or_else(|| env::var_os("HOME").map(|h| PathBuf::from(h).join(".config")));
+    if let Some(dir) = user_dir {
+        let path = dir.join("lenz").join("config.toml");
+        if path.is_file() {
+            paths.push(path);
+        }
+    }
+    if let Ok(cwd) = env::current_dir() {
+        paths.extend(find_project(&cwd));
+    }
+    paths
+}
+
+
+/// This function returns the nearest project's config file, looking in `dir` and then in its parents.
+pub fn find_project(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors().map(|d| d.join(PROJECT_FILE)).find(|p| p.is_file())
+}
+
+
+/// The task of this function is to read the settings of every config file found by `discover`.
+///
+/// # Errors
+///
+/// If a config file cannot be read or is not valid.
+pub fn load() -> Result<Vec<Setting>, LenzError> {
+    let mut settings = Vec::new();
+    for path in discover() {
+        let path = path.to_string_lossy().into_owned();
+        let text = fs::read_to_string(&path).map_err(|e| LenzError::read(&path, e))?;
+        settings.extend(parse(&path, &text)?);
+    }
+    Ok(settings)
+}
+
+
+/// The task of this function is to parse the contents of a config file.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file, which is kept in each `Setting`.
+/// * `text` - The contents of the file.
+///
+/// # Returns
+///
+/// The settings, in the order they were written.
+///
+/// # Errors
+///
+/// If a line is not a valid setting.
+pub fn parse(path: &str, text: &str) -> Result<Vec<Setting>, LenzError> {
+    let mut settings = Vec::new();
+    for (i, raw) in text.lines().enumerate() {
+        let error = |reason: String| LenzError::Config { path: path.to_string(), line: i + 1, reason };
+        let content = strip_comment(raw).trim();
+        if content.is_empty() {
+            continue;
+        }
+        if content.starts_with('[') {
+            return Err(error(String::from("Tables are not supported.")));
+        }
+        let Some((key, value)) = content.split_once('=') else {
+            return Err(error(String::from("A setting should be written as 'key = value'.")));
+        };
+        let key = key.trim().trim_matches('"').replace('_', "-");
+        let Some(&(key, kind)) = KEYS.iter().find(|(k, _)| *k == key) else {
+            return Err(error(format!("There is no setting named '{}'.", key)));
+        };
+        let Some(value) = parse_value(value.trim()) else {
+            return Err(error(format!("The value of '{}' is not valid.", key)));
+        };
+        let expected = match (kind, &value) {
+            (Kind::Flag, Value::Bool(_)) => None,
+            (Kind::Flag, _) => Some("true or false"),
+            (Kind::Value, Value::Str(_) | Value::Int(_)) => None,
+            (Kind::Value, _) => Some("a string or a number"),
+            (Kind::List, Value::List(_) | Value::Str(_)) => None,
+            (Kind::List, _) => Some("an array of strings"),
+        };
+        if let Some(expected) = expected {
+            return Err(error(format!("The value of '{}' should be {}.", key, expected)));
+        }
+        settings.push(Setting { key, value, path: path.to_string(), line: i + 1 });
+    }
+    Ok(settings)
+}
+
+
+/// This function returns the config key of a command-line option, if it has one.
+pub fn key_of(option: &str) -> Option<&'static str> {
+    match option {
+        "-i" | "--ignore-case" | "-s" | "--case-sensitive" | "-S" | "--smart-case" => Some("case"),
+        "--format" | "--vimgrep" => Some("format"),
+        "--mmap" | "--no-mmap" => Some("mmap"),
+        "-C" => Some("context"),
+        "-m" => Some("max-count"),
+        "-b" => Some("byte-offset"),
+        "-o" => Some("only-matching"),
+        "-0" => Some("null"),
+        _ => {
+            let name = option.strip_prefix("--")?;
+            KEYS.iter().map(|(k, _)| *k).find(|k| *k == name)
+        },
+    }
+}
+
+
+/// The task of this function is to show the effective settings, along with where each one comes from
+/// (`default`, `command line` or the line of a config file). This is what `--print-config` displays.
+///
+/// # Arguments
+///
+/// * `opts` - The options, after the config files and the command line were applied.
+/// * `sources` - Where each setting that is not a default comes from, by its key.
+///
+/// # Returns
+///
+/// A `String` with one `key = value` line per setting, ending with a line break.
+pub fn render(opts: &Options, sources: &HashMap<&'static str, String>) -> String {
+    let rows: Vec<(String, &str)> = KEYS.iter().map(|(key, _)| {
+        (
+            format!("{} = {}", key, value_of(opts, key)),
+            sources.get(key).map_or("default", String::as_str),
+        )
+    }).collect();
+    let width = rows.iter().map(|(row, _)| str_width(row)).max().unwrap_or(0);
+    rows.iter()
+        .map(|(row, source)| format!("{}{}  # {}\n", row, padding(row, width), source))
+        .collect()
+}
+
+
+/// This function returns the effective value of a setting, written like in a config file.
+fn value_of(opts: &Options, key: &str) -> String {
+    let number = |n: Option<usize>| n.map_or(String::from("(none)"), |n| n.to_string());
+    match key {
+        "case" => json::string(opts.case.name()),
+        "format" => json::string(opts.format.name()),
+        "color" => json::string(opts.color.name()),
+        "theme" => json::string(&opts.theme),
+        "colors" => {
+            let items: Vec<String> = opts.colors.iter().map(|c| json::string(&c.to_string())).collect();
+            format!("[{}]", items.join(", "))
+        },
+        "hyperlink-format" => json::string(&opts.hyperlink_format),
+        "exclude" => {
+            let items: Vec<String> = opts.exclude.iter().map(|p| json::string(p)).collect();
+            format!("[{}]", items.join(", "))
+        },
+        "context" => opts.context.to_string(),
+        "columns" => {
+            let names: Vec<&str> = opts.columns.iter().map(|c| c.name()).collect();
+            json::string(&names.join(","))
+        },
+        "max-count" => number(opts.max_count),
+        "max-total" => number(opts.max_total),
+        "max-columns" => number(opts.max_columns),
+        "wrap" => opts.wrap.to_string(),
+        "tab-width" => opts.tab_width.to_string(),
+        "column" => opts.column.to_string(),
+        "byte-offset" => opts.byte_offset.to_string(),
+        "only-matching" => opts.only_matching.to_string(),
+        "null" => opts.null.to_string(),
+        "mmap" => opts.mmap.map_or(String::from("(auto)"), |m| m.to_string()),
+        _ => String::new(),
+    }
+}
+
+
+/// This function removes a `#` comment from a line, unless the `#` is inside a string.
+fn strip_comment(line: &str) -> &str {
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match (quote, c) {
+            (Some('"'), '\\') if !escaped => {
+                escaped = true;
+                continue;
+            },
+            (Some(q), c) if c == q && !escaped => quote = None,
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '#') => return &line[..i],
+            _ => {},
+        }
+        escaped = false;
+    }
+    line
+}
+
+
+/// The task of this function is to parse a value: a string, a whole number, `true`/`false`
+/// or an array of strings written on one line.
+fn parse_value(raw: &str) -> Option<Value> {
+    match raw {
+        "true" => return Some(Value::Bool(true)),
+        "false" => return Some(Value::Bool(false)),
+        _ => {},
+    }
+    if let Ok(n) = raw.parse::<u64>() {
+        return Some(Value::Int(n));
+    }
+    if let Some(inner) = raw.strip_prefix('[').and_then(|r| r.strip_suffix(']')) {
+        let mut items = Vec::new();
+        let mut rest = inner.trim_start();
+        while !rest.is_empty() {
+            let (item, after) = parse_string(rest)?;
+            items.push(item);
+            rest = after.trim_start();
+            if let Some(after) = rest.strip_prefix(',') {
+                rest = after.trim_start();
+            } else if !rest.is_empty() {
+                return None;
+            }
+        }
+        return Some(Value::List(items));
+    }
+    match parse_string(raw)? {
+        (s, "") => Some(Value::Str(s)),
+        _ => None,
+    }
+}
+
+
+/// The task of this function is to parse a basic (`"..."`) or literal (`'...'`) string
+/// at the start of a text.
+///
+/// # Returns
+///
+/// The string, and what comes after it.
+fn parse_string(raw: &str) -> Option<(String, &str)> {
+    if let Some(body) = raw.strip_prefix('\'') {
+        let end = body.find('\'')?;
+        return Some((body[..end].to_string(), &body[end + 1..]));
+    }
+    let body = raw.strip_prefix('"')?;
+    let mut s = String::new();
+    let mut chars = body.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Some((s, &body[i + 1..])),
+            '\\' => s.push(match chars.next()?.1 {
+                'n' => '\n',
+                't' => '\t',
+                '"' => '"',
+                '\\' => '\\',
+                _ => return None,
+            }),
+            c => s.push(c),
+        }
+    }
+    None
+}
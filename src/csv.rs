@@ -0,0 +1,209 @@
+//! This module is related to the **tabular** output of `lenz` (`--format csv` and `--format tsv`),
+//! which can be loaded into spreadsheets. Each match is a record, and the columns
+//! of the records are chosen with `--columns`. CSV fields are quoted as RFC 4180 asks;
+//! TSV fields cannot be quoted, so tabs and line breaks in them are escaped instead.
+
+
+use std::io::{ Error, Write, };
+use crate::cells::{ Cell, };
+use crate::errors::{ LenzError, };
+use crate::printer::{ Printer, get_truncated_footer, };
+use crate::style::{ Look, };
+
+
+/// A column of the tabular output.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Column {
+    /// The path of the file.
+    Path,
+    /// The line number.
+    Line,
+    /// The column of the match, counted in display columns from `1`.
+    Column,
+    /// The absolute byte offset of the match.
+    ByteOffset,
+    /// The text of the match.
+    Match,
+    /// The whole line.
+    Text,
+    /// The query that matched.
+    Query,
+}
+
+
+/// The columns that are used if `--columns` is not given.
+pub const DEFAULT_COLUMNS: [Column; 4] = [Column::Path, Column::Line, Column::Column, Column::Match];
+
+
+impl Column {
+    /// The task of this method is to parse the name of a column.
+    ///
+    /// # Returns
+    ///
+    /// `Some(Column)` if the name is known, otherwise `None`.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "path" => Some(Column::Path),
+            "line" => Some(Column::Line),
+            "column" => Some(Column::Column),
+            "offset" => Some(Column::ByteOffset),
+            "match" => Some(Column::Match),
+            "text" => Some(Column::Text),
+            "query" => Some(Column::Query),
+            _ => None,
+        }
+    }
+
+    /// The task of this method is to parse the value of `--columns`, like `path,line,match`.
+    ///
+    /// # Returns
+    ///
+    /// `Some(Vec<Column>)` if every name is known, otherwise `None`.
+    pub fn parse_list(value: &str) -> Option<Vec<Self>> {
+        value.split(',').map(|name| Column::parse(name.trim())).collect()
+    }
+
+    /// This method returns the name of the column, as it is written in the header.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Column::Path => "path",
+            Column::Line => "line",
+            Column::Column => "column",
+            Column::ByteOffset => "offset",
+            Column::Match => "match",
+            Column::Text => "text",
+            Column::Query => "query",
+        }
+    }
+}
+
+
+/// The task of this function is to make a field safe to be placed in a record.
+///
+/// # Arguments
+///
+/// * `text` - The contents of the field.
+/// * `tsv` - Whether the record is tab-separated.
+///
+/// # Returns
+///
+/// The field, quoted (CSV) or escaped (TSV) if needed.
+pub fn field(text: &str, tsv: bool) -> String {
+    if tsv {
+        return text.replace('\\', "\\\\")
+                   .replace('\t', "\\t")
+                   .replace('\n', "\\n")
+                   .replace('\r', "\\r");
+    }
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+
+/// The task of this function is to join the fields of a record.
+///
+/// # Arguments
+///
+/// * `fields` - The contents of the fields.
+/// * `tsv` - Whether the record is tab-separated.
+///
+/// # Returns
+///
+/// The record, without its line break.
+pub fn record(fields: &[String], tsv: bool) -> String {
+    let fields: Vec<String> = fields.iter().map(|f| field(f, tsv)).collect();
+    fields.join(if tsv { "\t" } else { "," })
+}
+
+
+/// The task of this function is to describe each match of a line as a CSV or TSV record.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file the line belongs to.
+/// * `cell` - The line.
+/// * `columns` - The columns of the records.
+/// * `query` - The query, for the `query` column.
+/// * `tsv` - Whether the records are tab-separated.
+///
+/// # Returns
+///
+/// A `Vec<String>` containing one record per match, without line breaks.
+pub fn records(path: &str, cell: &Cell, columns: &[Column], query: &str, tsv: bool) -> Vec<String> {
+    cell.spans().into_iter().map(|(x, n)| {
+        let fields: Vec<String> = columns.iter().map(|column| match column {
+            Column::Path => path.to_string(),
+            Column::Line => cell.lineno().to_string(),
+            Column::Column => cell.column(x).to_string(),
+            Column::ByteOffset => (cell.offset() + x as u64).to_string(),
+            Column::Match => cell.line()[x..x + n].to_string(),
+            Column::Text => cell.line().to_string(),
+            Column::Query => query.to_string(),
+        }).collect();
+        record(&fields, tsv)
+    }).collect()
+}
+
+
+/// This function returns the header record naming the columns.
+pub fn header(columns: &[Column], tsv: bool) -> String {
+    let names: Vec<String> = columns.iter().map(|c| c.name().to_string()).collect();
+    record(&names, tsv)
+}
+
+
+/// This function returns the line break ending each record: `CRLF` for CSV, as RFC 4180 asks.
+pub fn line_break(tsv: bool) -> &'static str {
+    if tsv { "\n" } else { "\r\n" }
+}
+
+
+/// The `Printer` of the CSV and TSV formats: a header, then one record per match.
+/// Everything else goes to `err`, so it is not taken for a record.
+pub struct Csv<W: Write, E: Write> {
+    out: W,
+    err: E,
+    columns: Vec<Column>,
+    query: String,
+    tsv: bool,
+}
+
+
+impl<W: Write, E: Write> Csv<W, E> {
+    /// `Csv` Constructor.
+    pub fn new(out: W, err: E, columns: &[Column], query: &str, tsv: bool) -> Self {
+        Self { out, err, columns: columns.to_vec(), query: query.to_string(), tsv }
+    }
+}
+
+
+impl<W: Write, E: Write> Printer for Csv<W, E> {
+    fn begin(&mut self) -> Result<(), Error> {
+        write!(self.out, "{}{}", header(&self.columns, self.tsv), line_break(self.tsv))
+    }
+
+    fn matched(&mut self, path: &str, cell: &Cell) -> Result<(), Error> {
+        for record in records(path, cell, &self.columns, &self.query, self.tsv) {
+            write!(self.out, "{}{}", record, line_break(self.tsv))?;
+        }
+        Ok(())
+    }
+
+    fn error(&mut self, error: &LenzError) -> Result<(), Error> {
+        writeln!(self.err, "{}", error.plain())
+    }
+
+    fn notice(&mut self, text: &str) -> Result<(), Error> {
+        writeln!(self.err, "{}", text)
+    }
+
+    fn finish(&mut self, truncated: Option<&str>) -> Result<(), Error> {
+        if let Some(limits) = truncated {
+            writeln!(self.err, "{}", get_truncated_footer(&Look::plain(), limits))?;
+        }
+        Ok(())
+    }
+}
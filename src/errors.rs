@@ -1,37 +1,143 @@
 //! This module is related to structuring and displaying **errors** in `lenz`.
-//! In this module, errors are structured and can be displayed in the form
-//! of a specific message with a specific format so that the
-//! program can use this specific structure whenever it needs to **handle errors**.
+//! In this module, errors are structured by their kind, along with the path
+//! and the underlying `io::Error` they come from, so that the
+//! program can tell them apart whenever it needs to **handle errors**,
+//! and can display them in color, as plain text or as JSON.
 
 
-use crate::style::{ ANSIStyle, };
+use std::fmt;
+use std::io::{ self, Error, ErrorKind, Write, };
+use crate::json;
+use crate::style::{ ANSIStyle, ColorChoice, Look, };
+use crate::theme::{ Slot, };
 use crate::help ::{ get_usage, get_try, };
 
 
-/// The `ErrorMsg` structure, upon receiving a message,
-/// formats the error in a structured way and can display it if needed.
-/// This structure can be used to **control**, **handle**, and **display** errors in the program.
-pub struct ErrorMsg {
-    msg: String,
+/// The `LenzError` enum holds every error that `lenz` can run into.
+/// This enum can be used to **control**, **handle**, and **display** errors in the program.
+#[derive(Debug)]
+pub enum LenzError {
+    /// The command line is not valid, for a reason that is described by the message.
+    Usage(String),
+    /// An option was given without its value.
+    MissingValue { option: String },
+    /// The value of an option is not valid.
+    InvalidValue { option: String, value: String },
+    /// An option that `lenz` does not know.
+    InvalidOption { option: String },
+    /// A path that does not exist.
+    NotFound { path: String },
+    /// A path that exists, but is not a file (like a directory).
+    NotAFile { path: String },
+    /// A file that could not be opened or read.
+    Read { path: String, source: Error },
+    /// A file that could not be written.
+    Write { path: String, source: Error },
+    /// A config file that is not valid, at a line.
+    Config { path: String, line: usize, reason: String },
 }
 
 
-impl ErrorMsg {
-    /// `ErrorMsg` Constructor.
-    pub fn new(msg: String) -> Self {
-        Self { msg }
+impl LenzError {
+    /// The task of this function is to turn a failure to open or read a file into a `LenzError`.
+    /// A file that is missing is `NotFound`, and anything else is `Read`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file.
+    /// * `source` - The error of the operating system.
+    pub fn read(path: &str, source: Error) -> Self {
+        match source.kind() {
+            ErrorKind::NotFound => LenzError::NotFound { path: path.to_string() },
+            _ => LenzError::Read { path: path.to_string(), source },
+        }
+    }
+
+    /// This method returns the kind of the error as a short name, like `not_found`.
+    /// A `Read` error whose permission was denied is `permission_denied`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            LenzError::Usage(_) => "usage",
+            LenzError::MissingValue { .. } => "missing_value",
+            LenzError::InvalidValue { .. } => "invalid_value",
+            LenzError::InvalidOption { .. } => "invalid_option",
+            LenzError::NotFound { .. } => "not_found",
+            LenzError::NotAFile { .. } => "not_a_file",
+            LenzError::Read { source, .. } if source.kind() == ErrorKind::PermissionDenied => "permission_denied",
+            LenzError::Read { .. } => "read",
+            LenzError::Write { .. } => "write",
+            LenzError::Config { .. } => "config",
+        }
+    }
+
+    /// This method returns the path the error is about, if there is one.
+    pub fn path(&self) -> Option<&str> {
+        match self {
+            LenzError::NotFound { path }
+            | LenzError::NotAFile { path }
+            | LenzError::Read { path, .. }
+            | LenzError::Write { path, .. }
+            | LenzError::Config { path, .. } => Some(path),
+            _ => None,
+        }
+    }
+
+    /// The task of this method is to render the error message,
+    /// with the paths, options and values in the `path` style of the theme of the `look` (so unstyled when it has no colors).
+    pub fn colored(&self, look: &Look) -> String {
+        self.render(|s| look.paint(Slot::Path, &format!("'{}'", s)))
+    }
+
+    /// The task of this method is to render the error message without any styling.
+    pub fn plain(&self) -> String {
+        self.render(|s| format!("'{}'", s))
     }
 
-    /// `msg` attr getter.
-    pub fn msg(&self) -> &str {
-        &self.msg
+    /// The task of this method is to render the error as a JSON object,
+    /// with its `kind`, its `path` and its plain message.
+    pub fn to_json(&self) -> String {
+        json::error(self.kind(), self.path(), &self.plain())
     }
 
     /// This method can `raise` an error and **display** the error
-    /// in the form of a **standard CLI** message.
+    /// in the form of a **standard CLI** message on `stderr`, colored if it is a terminal
+    /// and `NO_COLOR` is not set.
     pub fn raise(&self) {
-        for part in self.gen_msg() {
-            eprintln!("{}", part);
+        let _ = self.write_to(&mut io::stderr(), &Look::for_stderr(ColorChoice::Auto));
+    }
+
+    /// The task of this method is to write the error, in the same form as `raise`, to `out`.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - Where the error is written.
+    /// * `look` - How the error is styled.
+    ///
+    /// # Errors
+    ///
+    /// If writing to `out` fails.
+    pub fn write_to(&self, out: &mut dyn Write, look: &Look) -> Result<(), Error> {
+        for part in self.gen_msg(look) {
+            writeln!(out, "{}", part)?;
+        }
+        Ok(())
+    }
+
+    /// The task of this method is to put the message together, with `quote` wrapped around the
+    /// paths, options and values.
+    fn render<F: Fn(&str) -> String>(&self, quote: F) -> String {
+        match self {
+            LenzError::Usage(msg) => msg.to_string(),
+            LenzError::MissingValue { option } => format!("The option {} expects a value.", quote(option)),
+            LenzError::InvalidValue { option, value } => {
+                format!("The value of {} is not valid: {}.", quote(option), quote(value))
+            },
+            LenzError::InvalidOption { option } => format!("This option is not valid: {}.", quote(option)),
+            LenzError::NotFound { path } => format!("File {} does not exist.", quote(path)),
+            LenzError::NotAFile { path } => format!("Path {} is not a file.", quote(path)),
+            LenzError::Read { path, source } => format!("File {} could not be read: {}.", quote(path), source),
+            LenzError::Write { path, source } => format!("File {} could not be written: {}.", quote(path), source),
+            LenzError::Config { path, line, reason } => format!("Config file {}, line {}: {}", quote(path), line, reason),
         }
     }
 
@@ -41,17 +147,34 @@ impl ErrorMsg {
     /// # Returns
     ///
     /// A **vector** (`Vec<String>`) containing messages (`String`)s generated in order.
-    fn gen_msg(&self) -> Vec<String> {
+    fn gen_msg(&self, look: &Look) -> Vec<String> {
         // Error message
         let error: String = format!(
-            "{}{}Error:{} {}",
-            ANSIStyle::FGRed.as_str(),
-            ANSIStyle::Bold.as_str(),
-            ANSIStyle::Reset.as_str(),
-            self.msg,
+            "{}{}{} {}",
+            look.ansi(ANSIStyle::Bold),
+            look.paint(Slot::Error, "Error:"),
+            look.ansi(ANSIStyle::Reset),
+            self.colored(look),
         );
         // Each will be placed into a vector
         // for printing, respectively.
-        vec![get_usage(), get_try(), error]
+        vec![get_usage(look), get_try(look), error]
+    }
+}
+
+
+impl fmt::Display for LenzError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.plain())
+    }
+}
+
+
+impl std::error::Error for LenzError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LenzError::Read { source, .. } | LenzError::Write { source, .. } => Some(source),
+            _ => None,
+        }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,308 @@
+//! This module is related to **following** files that keep growing, like `tail -f`.
+//! After the initial search, the files are polled and every new line
+//! that is appended to them is displayed as a matching `Cell`.
+//! Truncated files are read again from the start and rotated files
+//! (a new file under the same path) are reopened.
+
+
+use std::fs::{ self, File, Metadata, };
+use std::io::{ Error, ErrorKind, Read, Seek, SeekFrom, };
+use memchr::{ memchr_iter, };
+use std::thread::{ sleep, };
+use std::time::{ Duration, };
+use crate::cells::{ Cell, };
+use crate::errors::{ LenzError, };
+use crate::matcher::{ Matcher, };
+use crate::opts::{ Mode, Options, };
+use crate::printer::{ Printer, };
+use crate::style::{ ANSIStyle, Look, };
+
+
+/// The time between two checks of the followed files.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+/// The number of bytes read at once while counting the lines of a file.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+
+/// What happened to a followed file since the previous poll, apart from growing.
+#[derive(Debug, PartialEq)]
+pub enum FileEvent {
+    /// Nothing special happened.
+    Unchanged,
+    /// The file became shorter than what was already read.
+    Truncated,
+    /// Another file took its place (log rotation).
+    Rotated,
+}
+
+
+/// A file that is being followed.
+/// It remembers how far the file has been read and how many lines were seen.
+pub struct Follower {
+    path: String,
+    file: File,
+    id: u64,
+    offset: u64,
+    lineno: usize,
+    /// The last incomplete line, waiting for its newline.
+    pending: Vec<u8>,
+    /// The byte offset at which `pending` starts.
+    line_start: u64,
+}
+
+
+/// A line read by a `Follower`: its line number, its byte offset and its contents.
+pub type FollowedLine = (usize, u64, String);
+
+
+/// A file to follow: its path, and where its search read up to (see `ReadableFile::reached`).
+pub type FollowedFile = (String, Option<(usize, u64)>);
+
+
+impl Follower {
+    /// `Follower` Constructor.
+    /// Only the lines after `reached` are reported. Without it, the lines of the file are counted
+    /// to its end (a chunk at a time), so only lines appended after this call are reported.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file.
+    /// * `reached` - Where a search of the file read up to: the number of complete lines and the byte offset after them.
+    ///
+    /// # Errors
+    ///
+    /// If there is a problem opening or reading the file.
+    pub fn new(path: &str, reached: Option<(usize, u64)>) -> Result<Self, Error> {
+        let file = File::open(path)?;
+        let id = file_id(&file.metadata()?);
+        let mut follower = Self {
+            path: path.to_string(),
+            file,
+            id,
+            offset: 0,
+            lineno: 0,
+            pending: Vec::new(),
+            line_start: 0,
+        };
+        match reached {
+            Some((lineno, offset)) => {
+                follower.lineno = lineno;
+                follower.offset = offset;
+                follower.line_start = offset;
+            },
+            None => follower.skip_lines()?,
+        }
+        Ok(follower)
+    }
+
+    /// `path` attr getter.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// The task of this method is to check the file for changes and
+    /// return the complete lines that were appended since the previous call.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<(FileEvent, Vec<FollowedLine>), Error>` with what happened to the file
+    /// and the new lines along with their line numbers and byte offsets.
+    ///
+    /// # Errors
+    ///
+    /// If there is a problem reading the file.
+    pub fn poll(&mut self) -> Result<(FileEvent, Vec<FollowedLine>), Error> {
+        let mut lines = Vec::new();
+        let mut event = FileEvent::Unchanged;
+        // While a file is being rotated the path may briefly not exist;
+        // the old handle is kept until a new file appears.
+        if let Ok(meta) = fs::metadata(&self.path) {
+            if file_id(&meta) != self.id {
+                // Whatever was written to the old file before the rotation still counts.
+                lines = self.read_lines()?;
+                self.file = File::open(&self.path)?;
+                self.id = file_id(&self.file.metadata()?);
+                self.restart();
+                event = FileEvent::Rotated;
+            } else if meta.len() < self.offset {
+                self.restart();
+                event = FileEvent::Truncated;
+            }
+        }
+        lines.extend(self.read_lines()?);
+        Ok((event, lines))
+    }
+
+    /// Starts reading the file again from the beginning.
+    fn restart(&mut self) {
+        self.offset = 0;
+        self.lineno = 0;
+        self.pending.clear();
+        self.line_start = 0;
+    }
+
+    /// Counts the complete lines of the file from its start, a chunk at a time,
+    /// and moves `offset` to the end of the last one.
+    fn skip_lines(&mut self) -> Result<(), Error> {
+        let mut chunk = vec![0u8; CHUNK_SIZE];
+        let mut at: u64 = 0;
+        self.file.seek(SeekFrom::Start(0))?;
+        loop {
+            let n = match self.file.read(&mut chunk) {
+                Ok(0) => break,
+                Ok(n) => n,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            for p in memchr_iter(b'\n', &chunk[..n]) {
+                self.lineno += 1;
+                self.line_start = at + p as u64 + 1;
+            }
+            at += n as u64;
+        }
+        self.offset = self.line_start;
+        Ok(())
+    }
+
+    /// Reads everything after `offset` and splits it into complete, numbered lines.
+    /// Lines that are not valid UTF-8 are counted but not returned.
+    fn read_lines(&mut self) -> Result<Vec<FollowedLine>, Error> {
+        let mut buf = Vec::new();
+        self.file.seek(SeekFrom::Start(self.offset))?;
+        let mut at = self.offset;
+        self.offset += self.file.read_to_end(&mut buf)? as u64;
+        let mut lines = Vec::new();
+        let mut rest: &[u8] = &buf;
+        while let Some(p) = rest.iter().position(|&b| b == b'\n') {
+            self.pending.extend_from_slice(&rest[..p]);
+            rest = &rest[p + 1..];
+            at += p as u64 + 1;
+            self.lineno += 1;
+            let line = self.pending.strip_suffix(b"\r").unwrap_or(&self.pending);
+            lines.push((self.lineno, self.line_start, String::from_utf8_lossy(line).into_owned()));
+            self.pending.clear();
+            self.line_start = at;
+        }
+        self.pending.extend_from_slice(rest);
+        Ok(lines)
+    }
+}
+
+
+/// The task of this function is to follow files and display the new lines, until the output is closed.
+///
+/// # Arguments
+///
+/// * `paths` - The paths of the files to follow, along with where their search read up to.
+/// * `matcher` - The compiled query.
+/// * `opts` - The options entered by the user. In page view, every new line is shown,
+///   not only the matching ones.
+/// * `look` - How the new lines and the messages about the files are styled.
+/// * `printer` - Where the new lines, the messages about the files and the errors are sent.
+pub fn follow(paths: &[FollowedFile], matcher: &Matcher, opts: &Options, look: &Look, printer: &mut dyn Printer) {
+    let page_view = opts.mode == Mode::Page;
+    let mut followers: Vec<Follower> = Vec::new();
+    for (p, reached) in paths {
+        match Follower::new(p, *reached) {
+            Ok(follower) => followers.push(follower),
+            Err(e) => if report(printer, &LenzError::read(p, e)).is_err() {
+                return;
+            },
+        }
+    }
+    // A file that cannot be read is only reported once, until it can be read again.
+    let mut failing = vec![false; followers.len()];
+    let mut last: Option<usize> = None;
+    loop {
+        for (i, follower) in followers.iter_mut().enumerate() {
+            let (event, lines) = match follower.poll() {
+                Ok(polled) => {
+                    failing[i] = false;
+                    polled
+                },
+                Err(e) => {
+                    if !failing[i] && report(printer, &LenzError::read(follower.path(), e)).is_err() {
+                        return;
+                    }
+                    failing[i] = true;
+                    continue;
+                },
+            };
+            if let Some(notice) = get_event_notice(look, follower.path(), &event)
+                && printer.notice(&notice).is_err() {
+                return;
+            }
+            for (n, offset, line) in lines {
+                let cell = Cell::new(matcher, look, line, n, offset, 1);
+                let matched = cell.check_line();
+                if !page_view && !matched {
+                    continue;
+                }
+                // The head tells which file the following lines belong to.
+                let head = if paths.len() > 1 && last != Some(i) {
+                    last = Some(i);
+                    printer.file_start(follower.path(), None)
+                } else {
+                    Ok(())
+                };
+                let result = head.and_then(|_| if matched {
+                    printer.matched(follower.path(), &cell)
+                } else {
+                    printer.context(follower.path(), &cell)
+                });
+                if result.is_err() {
+                    return;
+                }
+            }
+        }
+        sleep(POLL_INTERVAL);
+    }
+}
+
+
+/// This function returns the notice of a truncated or rotated file, if something happened to it.
+fn get_event_notice(look: &Look, path: &str, event: &FileEvent) -> Option<String> {
+    let what = match event {
+        FileEvent::Truncated => "was truncated",
+        FileEvent::Rotated => "was replaced",
+        FileEvent::Unchanged => return None,
+    };
+    Some(format!(
+        "{}* File {}'{}'{}{} {}; following from the start.{}",
+        look.ansi(ANSIStyle::FGYellow),
+        look.ansi(ANSIStyle::Italic),
+        path,
+        look.ansi(ANSIStyle::Reset),
+        look.ansi(ANSIStyle::FGYellow),
+        what,
+        look.ansi(ANSIStyle::Reset),
+    ))
+}
+
+
+/// The task of this function is to report a file that cannot be followed right away,
+/// since the search that gathers the errors never ends.
+///
+/// # Errors
+///
+/// If the printer fails to write.
+fn report(printer: &mut dyn Printer, error: &LenzError) -> Result<(), Error> {
+    printer.error(error)?;
+    printer.finish(None)
+}
+
+
+/// Returns a number that identifies the file behind a path (the inode on Unix).
+/// On other platforms, rotation cannot be detected and `0` is returned.
+fn file_id(meta: &Metadata) -> u64 {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        meta.ino()
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = meta;
+        0
+    }
+}
@@ -0,0 +1,60 @@
+//! This module is related to **excluding** files with glob patterns (`--exclude GLOB`).
+//! A pattern with a `/` is matched against the whole path, and a pattern without one
+//! is matched against every component of the path, so `target` excludes everything under it.
+//! `*` and `?` do not cross a `/`, while `**` does.
+
+
+/// The task of this function is to match a glob pattern against a text.
+///
+/// # Arguments
+///
+/// * `pattern` - The pattern, made of literal characters, `?`, `*` and `**`.
+/// * `text` - The text to be matched, like a path.
+///
+/// # Returns
+///
+/// `true` if the whole text matches the pattern.
+pub fn matches(pattern: &str, text: &str) -> bool {
+    let p: Vec<char> = pattern.chars().collect();
+    let t: Vec<char> = text.chars().collect();
+    match_chars(&p, &t)
+}
+
+
+/// The task of this function is to check whether a path is excluded by any of the patterns.
+///
+/// # Arguments
+///
+/// * `patterns` - The patterns given with `--exclude`.
+/// * `path` - The path of a file.
+pub fn is_excluded(patterns: &[String], path: &str) -> bool {
+    let path = path.strip_prefix("./").unwrap_or(path);
+    patterns.iter().any(|pattern| {
+        if pattern.contains('/') {
+            matches(pattern.strip_prefix("./").unwrap_or(pattern), path)
+        } else {
+            path.split('/').any(|component| matches(pattern, component))
+        }
+    })
+}
+
+
+/// This function matches the characters of a pattern against the characters of a text, backtracking on `*`.
+fn match_chars(p: &[char], t: &[char]) -> bool {
+    match p {
+        [] => t.is_empty(),
+        ['*', '*', rest @ ..] => {
+            // `**/` also matches no directory at all.
+            if let ['/', after @ ..] = rest && match_chars(after, t) {
+                return true;
+            }
+            (0..=t.len()).any(|i| match_chars(rest, &t[i..]))
+        },
+        ['*', rest @ ..] => {
+            let end = t.iter().position(|&c| c == '/').unwrap_or(t.len());
+            (0..=end).any(|i| match_chars(rest, &t[i..]))
+        },
+        ['?', rest @ ..] => matches!(t.first(), Some(&c) if c != '/') && match_chars(rest, &t[1..]),
+        [c, rest @ ..] => t.first() == Some(c) && match_chars(rest, &t[1..]),
+    }
+}
@@ -3,11 +3,15 @@
 //! and how to display the help text are implemented and written.
 
 
-use crate::style::{ ANSIStyle, };
+use std::io::{ self, Error, Write, };
+use crate::style::{ ANSIStyle, Look, };
+use crate::width::{ padding, str_width, };
 
 
 /// The space between the **options name** and their **descriptions**.
-const VOID: usize = 25;
+const VOID: usize = 29;
+/// The fewest spaces between a name longer than `VOID` and its description.
+const MIN_SPACE: usize = 2;
 /// The **version** of the application that is read directly from `Cargo.toml`.
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -57,48 +61,65 @@ impl<'a> HelpMessage<'a> {
     /// The task of this method is to display the program's `help` message.
     /// This means displaying **static** messages and program **options**.
     pub fn show(&self) {
-        println!("{}", self.usage);
-        println!("{}", self.desc);
-        println!("{}",
-            format!(
-                "{}Options:{}",
-                ANSIStyle::FGGreen.as_str(),
-                ANSIStyle::Reset.as_str(),
-            ),
-        );
+        let _ = self.write_to(&mut io::stdout());
+    }
+
+    /// The task of this method is to write the program's `help` message to `out`.
+    ///
+    /// # Errors
+    ///
+    /// If writing to `out` fails.
+    pub fn write_to(&self, out: &mut dyn Write) -> Result<(), Error> {
+        writeln!(out, "{}", self.usage)?;
+        writeln!(out, "{}", self.desc)?;
+        writeln!(
+            out,
+            "{}Options:{}",
+            ANSIStyle::FGGreen.as_str(),
+            ANSIStyle::Reset.as_str(),
+        )?;
         for option in &self.options {
-            let space = " ".repeat(VOID - option.name.len());
-            println!("  {}{}{}", option.name, space, option.desc);
+            let space = padding(option.name, VOID.max(str_width(option.name) + MIN_SPACE));
+            writeln!(out, "  {}{}{}", option.name, space, option.desc)?;
         }
+        Ok(())
     }
 }
 
 
 /// This function generates a constant string describing how to use the program.
 ///
+/// # Arguments
+///
+/// * `look` - How the text is styled.
+///
 /// # Returns
 ///
 /// A `String` containing the usage text.
-pub fn get_usage() -> String {
+pub fn get_usage(look: &Look) -> String {
     format!(
-        "{}Usage:{} lenz [QUERY] [FILEs]... [OPTION]",
-        ANSIStyle::FGGreen.as_str(),
-        ANSIStyle::Reset.as_str(),
+        "{}Usage:{} lenz [QUERY] [FILEs]... [OPTIONs]...",
+        look.ansi(ANSIStyle::FGGreen),
+        look.ansi(ANSIStyle::Reset),
     )
 }
 
 
 /// This function produces a static message regarding the use of the help option.
 ///
+/// # Arguments
+///
+/// * `look` - How the text is styled.
+///
 /// # Returns
 ///
 /// A `String` containing the `try lenz --help` text.
-pub fn get_try() -> String {
+pub fn get_try(look: &Look) -> String {
     format!(
         "Try {}{}'lenz --help'{} for help.\n",
-        ANSIStyle::FGWhite.as_str(),
-        ANSIStyle::Italic.as_str(),
-        ANSIStyle::Reset.as_str(),
+        look.ansi(ANSIStyle::FGWhite),
+        look.ansi(ANSIStyle::Italic),
+        look.ansi(ANSIStyle::Reset),
     )
 }
 
@@ -121,19 +142,139 @@ pub fn get_desc() -> String {
 pub fn get_help() -> HelpMessage<'static> {
     HelpMessage::new(
         get_desc(),
-        get_usage(),
+        get_usage(&Look::default()),
         vec![
             CLIOption::new(
                 "-i, --ignore-case",
                 "Perform case insensitive matching.",
             ),
+            CLIOption::new(
+                "-s, --case-sensitive",
+                "Perform case sensitive matching (default).",
+            ),
+            CLIOption::new(
+                "-S, --smart-case",
+                "Ignore case unless the query has uppercase.",
+            ),
             CLIOption::new(
                 "-c, --count",
                 "Counting matches in files.",
             ),
             CLIOption::new(
                 "-p, --page-view",
-                "Page view.",
+                "Page view (interactive in a terminal).",
+            ),
+            CLIOption::new(
+                "-l, --files-with-matches",
+                "List the files with matches.",
+            ),
+            CLIOption::new(
+                "-L, --files-without-match",
+                "List the files without matches.",
+            ),
+            CLIOption::new(
+                "-0, --null",
+                "End listed paths with NUL.",
+            ),
+            CLIOption::new(
+                "-C, --context N",
+                "Show N lines around each matched line.",
+            ),
+            CLIOption::new(
+                "-m, --max-count N",
+                "Show at most N matched lines per file.",
+            ),
+            CLIOption::new(
+                "--max-total N",
+                "Show at most N matched lines in total.",
+            ),
+            CLIOption::new(
+                "--max-columns N",
+                "Cut lines to N columns around the match.",
+            ),
+            CLIOption::new(
+                "--wrap",
+                "Wrap long lines to the terminal width.",
+            ),
+            CLIOption::new(
+                "--tab-width N",
+                "Count tab stops every N columns (default 8).",
+            ),
+            CLIOption::new(
+                "--column",
+                "Show the column of the first match.",
+            ),
+            CLIOption::new(
+                "-b, --byte-offset",
+                "Show the byte offset of the first match.",
+            ),
+            CLIOption::new(
+                "-o, --only-matching",
+                "Show each match on its own row.",
+            ),
+            CLIOption::new(
+                "--vimgrep",
+                "Show path:line:col:text per match.",
+            ),
+            CLIOption::new(
+                "--format FORMAT",
+                "Output format: text, json, sarif, markdown, csv, tsv.",
+            ),
+            CLIOption::new(
+                "--color WHEN",
+                "Color output: auto, always, never.",
+            ),
+            CLIOption::new(
+                "--theme NAME",
+                "Colors: default, light, colorblind.",
+            ),
+            CLIOption::new(
+                "--colors SPEC",
+                "Change a color, like 'match:bg:yellow'.",
+            ),
+            CLIOption::new(
+                "--hyperlink-format FMT",
+                "Link paths: default, vscode, or a URL template.",
+            ),
+            CLIOption::new(
+                "--columns LIST",
+                "CSV/TSV columns: path, line, column, offset, match, text, query.",
+            ),
+            CLIOption::new(
+                "--html FILE",
+                "Write an HTML report to FILE.",
+            ),
+            CLIOption::new(
+                "--exclude GLOB",
+                "Do not search the files matching GLOB.",
+            ),
+            CLIOption::new(
+                "--lines FIRST:LAST",
+                "Only search these lines.",
+            ),
+            CLIOption::new(
+                "--bytes OFFSET:LEN",
+                "Only search these bytes.",
+            ),
+            CLIOption::new(
+                "-f, --follow",
+                "Keep showing new matches as files grow.",
+            ),
+            CLIOption::new(
+                "-w, --watch",
+                "Search again whenever the files change.",
+            ),
+            CLIOption::new(
+                "--diff",
+                "With --watch, only show changed matches.",
+            ),
+            CLIOption::new(
+                "--mmap, --no-mmap",
+                "Force or disable memory-mapped search.",
+            ),
+            CLIOption::new(
+                "--print-config",
+                "Show the settings and where they come from.",
             ),
             CLIOption::new(
                 "-h, --help",
@@ -151,5 +292,11 @@ pub fn get_help() -> HelpMessage<'static> {
 /// The task of this function is to display the program version
 /// by referring to the `VERSION` constant.
 pub fn print_version() {
-    println!("{}", VERSION);
+    println!("{}", get_version());
+}
+
+
+/// This function returns the program version, read from the `VERSION` constant.
+pub fn get_version() -> &'static str {
+    VERSION
 }
\ No newline at end of file
@@ -0,0 +1,373 @@
+//! This module is related to the **HTML** report of `lenz` (`--html report.html`).
+//! The report is a single static file holding the same information as the terminal:
+//! a collapsible section per file with its head and its cells, followed by the errors report.
+//! Every `ANSIStyle` used in the terminal is mapped to a CSS class of the same look.
+
+
+use std::fs;
+use std::io::{ Error, Write, };
+use crate::cells::{ Cell, Gutter, };
+use crate::errors::{ LenzError, };
+use crate::matcher::{ Matcher, };
+use crate::printer::{ Printer, get_errors_report, get_truncated_footer, };
+use crate::rf::{ Shown, };
+use crate::style::{ ANSIStyle, Color, Look, };
+use crate::theme::{ Slot, Style, Theme, };
+
+
+/// The style sheet of the report, with a class for each `ANSIStyle`.
+const CSS: &str = "\
+body { background: #1e1e1e; color: #d4d4d4; font-family: monospace; margin: 2em; }
+summary { cursor: pointer; margin: 1em 0; }
+table { border-collapse: collapse; }
+td { padding: 0 0.5em; white-space: pre; vertical-align: top; }
+td.lineno { text-align: right; }
+.bold { font-weight: bold; } .italic { font-style: italic; }
+.underline { text-decoration: underline; } .blink { text-decoration: blink; }
+.fg-black { color: #000000; } .fg-red { color: #cd3131; } .fg-green { color: #0dbc79; }
+.fg-yellow { color: #e5e510; } .fg-blue { color: #2472c8; } .fg-magenta { color: #bc3fbc; }
+.fg-cyan { color: #11a8cd; } .fg-white { color: #e5e5e5; } .fg-def { color: inherit; }
+.bg-black { background: #000000; } .bg-red { background: #cd3131; } .bg-green { background: #0dbc79; }
+.bg-yellow { background: #e5e510; } .bg-blue { background: #2472c8; } .bg-magenta { background: #bc3fbc; }
+.bg-cyan { background: #11a8cd; } .bg-white { background: #e5e5e5; } .bg-def { background: inherit; }
+";
+
+
+/// The task of this function is to escape the characters of a text that have a meaning in HTML.
+///
+/// # Arguments
+///
+/// * `text` - The text to be escaped.
+///
+/// # Returns
+///
+/// A new `String` that can be placed in an HTML document as it is.
+pub fn escape(text: &str) -> String {
+    let mut s = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => s.push_str("&amp;"),
+            '<' => s.push_str("&lt;"),
+            '>' => s.push_str("&gt;"),
+            '"' => s.push_str("&quot;"),
+            '\'' => s.push_str("&#39;"),
+            c => s.push(c),
+        }
+    }
+    s
+}
+
+
+/// The task of this function is to wrap an escaped text in an element with the class of a style.
+///
+/// # Arguments
+///
+/// * `style` - The style of the text.
+/// * `html` - The text, already escaped.
+pub fn styled(style: ANSIStyle, html: &str) -> String {
+    format!("<span class=\"{}\">{}</span>", style.css_class(), html)
+}
+
+
+/// The task of this function is to wrap an escaped text in an element with the classes
+/// of the style of a slot in the theme.
+///
+/// # Arguments
+///
+/// * `theme` - The theme of the report.
+/// * `slot` - The part of the output the text is.
+/// * `html` - The text, already escaped.
+pub fn themed(theme: &Theme, slot: Slot, html: &str) -> String {
+    format!("{}{}</span>", open(&theme.style(slot)), html)
+}
+
+
+/// This function returns the opening tag of an element with the look of a `Style`.
+/// Attributes and basic colors get their CSS class, and the other colors are written inline.
+pub fn open(style: &Style) -> String {
+    let attrs = [(style.bold, "bold"), (style.italic, "italic"), (style.underline, "underline")];
+    let mut classes: Vec<String> = attrs.iter()
+                                        .filter(|(on, _)| *on)
+                                        .map(|(_, class)| class.to_string())
+                                        .collect();
+    let mut inline = Vec::new();
+    for (color, prefix, property) in [(style.fg, "fg", "color"), (style.bg, "bg", "background")] {
+        match color {
+            Some(c @ Color::Basic(_)) => classes.push(format!("{}-{}", prefix, c)),
+            Some(c) => {
+                let (r, g, b) = c.rgb();
+                inline.push(format!("{}: #{:02x}{:02x}{:02x};", property, r, g, b));
+            },
+            None => {},
+        }
+    }
+    let mut tag = String::from("<span");
+    if !classes.is_empty() {
+        tag.push_str(&format!(" class=\"{}\"", classes.join(" ")));
+    }
+    if !inline.is_empty() {
+        tag.push_str(&format!(" style=\"{}\"", inline.join(" ")));
+    }
+    tag.push('>');
+    tag
+}
+
+
+/// The task of this function is to turn a text styled with `ANSI escape codes` into HTML.
+/// Every known code (a single one, or several joined like `Style` writes them) opens an element
+/// with the same look, and `Reset` closes them all.
+///
+/// # Arguments
+///
+/// * `text` - The styled text, like the messages of the errors report.
+///
+/// # Returns
+///
+/// A new `String` containing the escaped and styled text.
+pub fn from_ansi(text: &str) -> String {
+    let mut s = String::with_capacity(text.len());
+    let mut opened = 0;
+    let mut rest = text;
+    while let Some(at) = rest.find("\x1b[") {
+        s.push_str(&escape(&rest[..at]));
+        let Some(end) = rest[at..].find('m') else {
+            rest = &rest[at..];
+            break;
+        };
+        match &rest[at + 2..at + end] {
+            "" | "0" => {
+                s.push_str(&"</span>".repeat(opened));
+                opened = 0;
+            },
+            params => if let Some(style) = Style::from_sgr(params) {
+                s.push_str(&open(&style));
+                opened += 1;
+            },
+        }
+        rest = &rest[at + end + 1..];
+    }
+    s.push_str(&escape(rest));
+    s.push_str(&"</span>".repeat(opened));
+    s
+}
+
+
+/// The task of this function is to `highlight` the matches of a `Matcher` in a line, like `hg_matches`.
+///
+/// # Arguments
+///
+/// * `theme` - The theme of the report.
+/// * `matcher` - The compiled query.
+/// * `text` - The line.
+///
+/// # Returns
+///
+/// A new `String` containing the escaped line with its matches highlighted.
+pub fn highlight(theme: &Theme, matcher: &Matcher, text: &str) -> String {
+    highlight_spans(theme, text, matcher.find_iter(text))
+}
+
+
+/// This function highlights the given `(start, len)` spans of a line, which are in order and do not overlap.
+fn highlight_spans(theme: &Theme, text: &str, spans: impl IntoIterator<Item = (usize, usize)>) -> String {
+    let mut s = String::with_capacity(text.len());
+    let mut l = 0;
+    for (x, n) in spans {
+        s.push_str(&escape(&text[l..x]));
+        s.push_str(&themed(theme, Slot::Match, &escape(&text[x..x+n])));
+        l = x + n;
+    }
+    s.push_str(&escape(&text[l..]));
+    s
+}
+
+
+/// This function returns the row of a `Cell`: its position in the gutter and its line.
+///
+/// # Arguments
+///
+/// * `theme` - The theme of the report.
+/// * `position` - The position of the cell, like `12:5`.
+/// * `slot` - The slot of the theme that styles the position.
+/// * `line` - The line, already escaped and highlighted.
+pub fn row(theme: &Theme, position: &str, slot: Slot, line: &str) -> String {
+    format!(
+        "<tr><td class=\"lineno\">{}</td><td>{}</td></tr>",
+        themed(theme, slot, &escape(position)),
+        line,
+    )
+}
+
+
+/// The task of this function is to describe a line as a row of the report,
+/// looking the same as it does in the terminal.
+///
+/// # Arguments
+///
+/// * `theme` - The theme of the report.
+/// * `cell` - The line.
+/// * `lineno_color` - Whether the line number of a matched line gets the `matched-lineno` style (page view).
+/// * `gutter` - What to display next to the line number.
+///
+/// # Returns
+///
+/// A `String` containing the row.
+pub fn cell_row(theme: &Theme, cell: &Cell, lineno_color: bool, gutter: Gutter) -> String {
+    let spans = cell.spans();
+    let slot = if lineno_color && !spans.is_empty() { Slot::MatchedLineno } else { Slot::Lineno };
+    row(theme, &cell.get_position(gutter), slot, &highlight_spans(theme, cell.line(), spans))
+}
+
+
+/// This function returns the collapsible section of a file, with its head and its rows.
+///
+/// # Arguments
+///
+/// * `theme` - The theme of the report.
+/// * `path` - The path of the file.
+/// * `count` - The number of matching occurrences.
+/// * `rows` - The rows made by `row`. Without rows (when counting), the section cannot be opened.
+pub fn section(theme: &Theme, path: &str, count: usize, rows: &[String]) -> String {
+    let head = format!(
+        "{} {} {}",
+        styled(ANSIStyle::Bold, "- Filename:"),
+        themed(theme, Slot::Path, &escape(&format!("'{}'", path))),
+        themed(theme, Slot::Count, &format!("[{}]", count)),
+    );
+    if rows.is_empty() {
+        return format!("<p>{}</p>", head);
+    }
+    format!(
+        "<details open><summary>{}</summary><table>{}</table></details>",
+        head,
+        rows.join(""),
+    )
+}
+
+
+/// The task of this function is to put the sections of a search together into an HTML document.
+///
+/// # Arguments
+///
+/// * `theme` - The theme of the report.
+/// * `query` - The string that was searched for.
+/// * `sections` - The sections made by `section`.
+/// * `errors` - The messages of the errors report, which may be styled with `ANSI escape codes`.
+/// * `notes` - Other messages about the search, like truncated results.
+///
+/// # Returns
+///
+/// A `String` containing the whole document.
+pub fn report(theme: &Theme, query: &str, sections: &[String], errors: &[String], notes: &[String]) -> String {
+    let mut body = sections.join("\n");
+    for note in notes {
+        body.push_str(&format!("\n<p>{}</p>", from_ansi(note)));
+    }
+    if !errors.is_empty() {
+        let items: Vec<String> = errors.iter().enumerate().map(|(i, e)| {
+            format!("<li>{} {}</li>", themed(theme, Slot::Error, &format!("[{}]", i + 1)), from_ansi(e))
+        }).collect();
+        body.push_str(&format!(
+            "\n<h3>{}</h3><ul>{}</ul>",
+            themed(theme, Slot::Error, "* Errors Report:"),
+            items.join(""),
+        ));
+    }
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>lenz: {}</title>\n<style>\n{}</style>\n</head>\n<body>\n<h2>{}</h2>\n{}\n</body>\n</html>\n",
+        escape(query),
+        CSS,
+        escape(&format!("lenz \"{}\"", query)),
+        body,
+    )
+}
+
+
+/// The `Printer` of the HTML report, which writes the report to its file when the search is over.
+/// The terminal (`out`) only gets the path of the report and the errors report.
+pub struct Html<W: Write> {
+    out: W,
+    look: Look,
+    report: String,
+    query: String,
+    gutter: Gutter,
+    page: bool,
+    /// The number of matches of the current file.
+    count: usize,
+    /// The rows of the current file.
+    rows: Vec<String>,
+    sections: Vec<String>,
+    errors: Vec<String>,
+}
+
+
+impl<W: Write> Html<W> {
+    /// `Html` Constructor.
+    /// The `report` is the path of the HTML file, which takes the theme of the `look`.
+    pub fn new(out: W, look: Look, report: &str, query: &str, gutter: Gutter, page: bool) -> Self {
+        Self {
+            out,
+            look,
+            report: report.to_string(),
+            query: query.to_string(),
+            gutter,
+            page,
+            count: 0,
+            rows: Vec::new(),
+            sections: Vec::new(),
+            errors: Vec::new(),
+        }
+    }
+}
+
+
+impl<W: Write> Printer for Html<W> {
+    fn needs_count(&self) -> bool {
+        true
+    }
+
+    fn file_start(&mut self, _path: &str, count: Option<usize>) -> Result<(), Error> {
+        self.count = count.unwrap_or(0);
+        self.rows.clear();
+        Ok(())
+    }
+
+    fn matched(&mut self, _path: &str, cell: &Cell) -> Result<(), Error> {
+        self.rows.push(cell_row(&self.look.theme, cell, self.page, self.gutter));
+        Ok(())
+    }
+
+    fn context(&mut self, _path: &str, cell: &Cell) -> Result<(), Error> {
+        self.rows.push(cell_row(&self.look.theme, cell, true, self.gutter));
+        Ok(())
+    }
+
+    fn file_end(&mut self, path: &str, _shown: Shown) -> Result<(), Error> {
+        self.sections.push(section(&self.look.theme, path, self.count, &self.rows));
+        Ok(())
+    }
+
+    fn error(&mut self, error: &LenzError) -> Result<(), Error> {
+        self.errors.push(error.colored(&self.look));
+        Ok(())
+    }
+
+    fn finish(&mut self, truncated: Option<&str>) -> Result<(), Error> {
+        let notes: Vec<String> = truncated.map(|limits| get_truncated_footer(&self.look, limits)).into_iter().collect();
+        let written = fs::write(&self.report, report(&self.look.theme, &self.query, &self.sections, &self.errors, &notes));
+        if written.is_ok() {
+            writeln!(
+                self.out,
+                "{}* HTML report: {}{}",
+                self.look.ansi(ANSIStyle::Bold),
+                self.look.paint(Slot::Path, &format!("'{}'", self.report)),
+                self.look.ansi(ANSIStyle::Reset),
+            )?;
+        }
+        if !self.errors.is_empty() {
+            write!(self.out, "{}", get_errors_report(&self.look, &self.errors))?;
+        }
+        // A report that could not be written fails the search.
+        written.map_err(|e| Error::other(LenzError::Write { path: self.report.clone(), source: e }))
+    }
+}
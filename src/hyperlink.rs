@@ -0,0 +1,179 @@
+//! This module is related to the **hyperlinks** of `lenz` (`--hyperlink-format FORMAT`).
+//! Terminals that support `OSC 8` let the paths (and the line numbers) of the output be
+//! clicked, to open the file in a browser or an editor.
+//!
+//! A format is a URL template, where `{path}` is the absolute path of the file, `{line}` and `{col}`
+//! are the line and the column (both `1` for the head of a file) and `{host}` is the name of the machine.
+//! When the template has a `{line}`, the line numbers are linked too.
+//! The template of an output is held by its `Look`, and is empty when hyperlinks are off.
+//! So is its `PathCache`, which keeps the absolute path of the file being linked to.
+
+
+use std::cell::{ RefCell, };
+use std::env;
+use std::fs;
+
+
+/// The absolute paths of the files an output links to.
+/// Every line of a file asks again, so the last path that was turned into an absolute one is kept,
+/// along with the result.
+#[derive(Clone, Debug, Default)]
+pub struct PathCache {
+    last: RefCell<Option<(String, String)>>,
+}
+
+
+impl PathCache {
+    /// This method returns the absolute path of a file, resolving its links when it can.
+    pub fn absolute(&self, path: &str) -> String {
+        let mut last = self.last.borrow_mut();
+        if let Some((p, abs)) = last.as_ref() && p == path {
+            return abs.to_string();
+        }
+        let abs = fs::canonicalize(path)
+            .or_else(|_| env::current_dir().map(|d| d.join(path)))
+            .map_or_else(|_| path.to_string(), |p| p.to_string_lossy().into_owned());
+        *last = Some((path.to_string(), abs.clone()));
+        abs
+    }
+}
+
+
+impl PartialEq for PathCache {
+    /// What is cached does not change how an output looks.
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+
+/// The formats that can be given by name instead of a template.
+pub const ALIASES: [(&str, &str); 4] = [
+    ("default", "file://{host}{path}"),
+    ("vscode", "vscode://file{path}:{line}:{col}"),
+    ("cursor", "cursor://file{path}:{line}:{col}"),
+    ("idea", "idea://open?file={path}&line={line}&column={col}"),
+];
+
+
+/// The placeholders that can be used in a template.
+const PLACEHOLDERS: [&str; 4] = ["{path}", "{line}", "{col}", "{host}"];
+
+
+/// The task of this function is to parse the value of `--hyperlink-format`.
+///
+/// # Arguments
+///
+/// * `value` - One of `ALIASES`, `none`, or a template with `{path}` and an optional `{line}`, `{col}` and `{host}`.
+///
+/// # Returns
+///
+/// `Some(String)` with the template (empty for `none`) if the value is valid, otherwise `None`.
+pub fn parse_format(value: &str) -> Option<String> {
+    if value == "none" {
+        return Some(String::new());
+    }
+    if let Some((_, template)) = ALIASES.iter().find(|(name, _)| *name == value) {
+        return Some(template.to_string());
+    }
+    let mut rest = value;
+    while let Some(at) = rest.find('{') {
+        let end = rest[at..].find('}')? + at + 1;
+        if !PLACEHOLDERS.contains(&&rest[at..end]) {
+            return None;
+        }
+        rest = &rest[end..];
+    }
+    (value.contains("{path}") && value.contains(':')).then(|| value.to_string())
+}
+
+
+/// The task of this function is to fill a template in for a place in a file.
+///
+/// # Arguments
+///
+/// * `template` - The URL template.
+/// * `path` - The absolute path of the file.
+/// * `line` - The line number.
+/// * `col` - The column.
+///
+/// # Returns
+///
+/// The URL, with the path percent-encoded.
+pub fn fill(template: &str, path: &str, line: usize, col: usize) -> String {
+    let path = encode(path);
+    template.replace("{host}", &hostname())
+            .replace("{path}", &path)
+            .replace("{line}", &line.to_string())
+            .replace("{col}", &col.to_string())
+}
+
+
+/// The task of this function is to wrap a text in an `OSC 8` hyperlink.
+pub fn link(url: &str, text: &str) -> String {
+    format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, text)
+}
+
+
+/// The task of this function is to link a text (like the head of a file) to a file,
+/// if hyperlinks are on (the template is not empty).
+/// The absolute path of the file is taken from `paths`.
+///
+/// # Returns
+///
+/// The linked text, or the text itself.
+pub fn file(template: &str, paths: &PathCache, path: &str, text: &str) -> String {
+    if template.is_empty() {
+        return text.to_string();
+    }
+    link(&fill(template, &paths.absolute(path), 1, 1), text)
+}
+
+
+/// The task of this function is to link a text (like a line number) to a line of a file,
+/// if hyperlinks are on and the template has a `{line}`.
+/// The absolute path of the file is taken from `paths`.
+///
+/// # Returns
+///
+/// The linked text, or the text itself.
+pub fn line(template: &str, paths: &PathCache, path: &str, line: usize, col: usize, text: &str) -> String {
+    if !template.contains("{line}") {
+        return text.to_string();
+    }
+    link(&fill(template, &paths.absolute(path), line, col), text)
+}
+
+
+/// This function percent-encodes the bytes of a path that cannot be written as they are in a URL.
+fn encode(path: &str) -> String {
+    let mut s = String::with_capacity(path.len());
+    for b in path.bytes() {
+        if b.is_ascii_alphanumeric() || b"/-_.~".contains(&b) {
+            s.push(char::from(b));
+        } else {
+            s.push_str(&format!("%{:02X}", b));
+        }
+    }
+    s
+}
+
+
+/// This function returns the name of the machine, or an empty string if it is not known.
+#[cfg(target_os = "linux")]
+fn hostname() -> String {
+    let mut buf = [0u8; 256];
+    // SAFETY: The buffer is valid for its whole length, and is only read up to its first NUL.
+    if unsafe { libc::gethostname(buf.as_mut_ptr().cast(), buf.len()) } != 0 {
+        return String::new();
+    }
+    let end = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+    String::from_utf8_lossy(&buf[..end]).into_owned()
+}
+
+
+/// This function returns the name of the machine, or an empty string if it is not known.
+#[cfg(not(target_os = "linux"))]
+fn hostname() -> String {
+    env::var("HOSTNAME").unwrap_or_default()
+}
@@ -0,0 +1,143 @@
+//! This module is related to the **JSON** output of `lenz`.
+//! With `--format json`, every result is printed as one JSON object per line
+//! (JSON Lines), so the output can be consumed by other programs.
+//! Each object has a `type`: `match`, `context`, `count`, `truncated` or `error`.
+
+
+use crate::cells::{ Cell, };
+
+
+/// The task of this function is to turn a string into a JSON string literal.
+///
+/// # Arguments
+///
+/// * `text` - The string to be quoted.
+///
+/// # Returns
+///
+/// A `String` containing the quoted and escaped text.
+pub fn string(text: &str) -> String {
+    let mut s = String::with_capacity(text.len() + 2);
+    s.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => s.push_str("\\\""),
+            '\\' => s.push_str("\\\\"),
+            '\n' => s.push_str("\\n"),
+            '\r' => s.push_str("\\r"),
+            '\t' => s.push_str("\\t"),
+            c if (c as u32) < 0x20 => s.push_str(&format!("\\u{:04x}", c as u32)),
+            c => s.push(c),
+        }
+    }
+    s.push('"');
+    s
+}
+
+
+/// The task of this function is to describe a line of a file as a JSON object.
+/// Lines without a match (in page view) have the type `context`.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file the line belongs to.
+/// * `cell` - The line.
+///
+/// # Returns
+///
+/// A `String` containing one JSON object.
+pub fn line(path: &str, cell: &Cell) -> String {
+    let text = cell.line();
+    let spans: Vec<String> = cell.spans().into_iter().map(|(x, n)| {
+        format!(
+            "{{\"text\":{},\"start\":{},\"end\":{},\"column\":{}}}",
+            string(&text[x..x + n]),
+            x,
+            x + n,
+            cell.column(x),
+        )
+    }).collect();
+    let kind = if spans.is_empty() { "context" } else { "match" };
+    format!(
+        "{{\"type\":\"{}\",\"path\":{},\"line_number\":{},\"byte_offset\":{},\"line\":{},\"submatches\":[{}]}}",
+        kind,
+        string(path),
+        cell.lineno(),
+        cell.offset(),
+        string(text),
+        spans.join(","),
+    )
+}
+
+
+/// The task of this function is to describe each match of a line as its own JSON object (`--only-matching`).
+///
+/// # Arguments
+///
+/// * `path` - The path of the file the line belongs to.
+/// * `cell` - The line.
+///
+/// # Returns
+///
+/// A `Vec<String>` containing one JSON object per match.
+pub fn matches(path: &str, cell: &Cell) -> Vec<String> {
+    cell.spans().into_iter().map(|(x, n)| {
+        format!(
+            "{{\"type\":\"match\",\"path\":{},\"line_number\":{},\"column\":{},\"byte_offset\":{},\"text\":{}}}",
+            string(path),
+            cell.lineno(),
+            cell.column(x),
+            cell.offset() + x as u64,
+            string(&cell.line()[x..x + n]),
+        )
+    }).collect()
+}
+
+
+/// This function returns the JSON object reporting the number of matches in a file.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file.
+/// * `count` - The number of matches.
+pub fn count(path: &str, count: usize) -> String {
+    format!(
+        "{{\"type\":\"count\",\"path\":{},\"count\":{}}}",
+        string(path),
+        count,
+    )
+}
+
+
+/// This function returns the JSON object reporting an error.
+///
+/// # Arguments
+///
+/// * `kind` - The kind of the error, like `not_found`.
+/// * `path` - The path the error is about, if any.
+/// * `msg` - The error message, without any styling.
+pub fn error(kind: &str, path: Option<&str>, msg: &str) -> String {
+    format!(
+        "{{\"type\":\"error\",\"kind\":{},\"path\":{},\"message\":{}}}",
+        string(kind),
+        path.map_or(String::from("null"), string),
+        string(msg),
+    )
+}
+
+
+/// This function returns the JSON object noting that some results were left out
+/// because of `--max-count` or `--max-total`.
+///
+/// # Arguments
+///
+/// * `max_count` - The limit for each file, if any.
+/// * `max_total` - The limit for all the files together, if any.
+pub fn truncated(max_count: Option<usize>, max_total: Option<usize>) -> String {
+    let number = |n: Option<usize>| n.map_or(String::from("null"), |n| n.to_string());
+    format!(
+        "{{\"type\":\"truncated\",\"max_count\":{},\"max_total\":{}}}",
+        number(max_count),
+        number(max_total),
+    )
+}
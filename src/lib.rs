@@ -23,18 +23,215 @@
 //! lenz "query" /path/to/your/file.txt -i
 //! ```
 //!
+//! ### For ignoring the case only when the query is lowercase -> (-S, --smart-case):
+//!
+//! With (-s, --case-sensitive), the case is taken into account again.
+//!
+//! ```bash
+//! lenz "error" /path/to/your/file.txt -S
+//! ```
+//!
+//! ### For showing lines around each matched line -> (-C, --context N):
+//!
+//! ```bash
+//! lenz "panic" src/main.rs -C 2
+//! ```
+//!
+//! ### For skipping files -> (--exclude GLOB):
+//!
+//! A pattern without a `/` is matched against each part of the path, so `target` skips everything under it.
+//!
+//! ```bash
+//! lenz "TODO" src/ -w --exclude "*.bak"
+//! ```
+//!
 //! ### For Page-view -> (-p, --page-view):
 //!
 //! ```bash
 //! lenz "query" /path/to/your/file.txt -p
 //! ```
 //!
+//! In a terminal, page view is interactive: `j`/`k` (or the arrows) scroll a line, `Space`/`b` a page,
+//! `g`/`G` go to the top/bottom, `n`/`N` jump to the next/previous match, `/` changes the query
+//! and `q` goes on to the next file (`Q` quits). The status bar shows where the view is, like `match 3/57`.
+//! When the output is not a terminal, the whole file is displayed instead.
+//!
 //! ### For counting matches in files -> (-c, --count):
 //!
 //! ```bash
 //! lenz "query" /path/to/your/file.txt -c
 //! ```
 //!
+//! ### For choosing the memory-mapped search strategy -> (--mmap, --no-mmap):
+//!
+//! Files of 64 MiB or larger are memory-mapped automatically.
+//!
+//! ```bash
+//! lenz "query" /path/to/a/huge/file.log --no-mmap
+//! ```
+//!
+//! ### For showing the column and the byte offset of the first match of each line -> (--column, --byte-offset):
+//!
+//! Columns are counted in display columns (tabs and wide characters are taken into account).
+//! Tab stops are every 8 columns, or every `N` columns with (--tab-width N).
+//!
+//! ```bash
+//! lenz "query" /path/to/your/file.txt --column --byte-offset
+//! ```
+//!
+//! ### For listing the files with matches -> (-l, --files-with-matches) or without -> (-L, --files-without-match):
+//!
+//! Only the bare paths are printed. With `-0` (`--null`), they are separated by `NUL` bytes.
+//!
+//! ```bash
+//! lenz "TODO" src/*.rs -l -0 | xargs -0 wc -l
+//! ```
+//!
+//! ### For limiting the matched lines -> (-m, --max-count N) per file and (--max-total N) for all files:
+//!
+//! The files are read no further than needed, and a footer notes that the results were truncated.
+//!
+//! ```bash
+//! lenz "error" /var/log/syslog -m 20
+//! lenz "error" /var/log/*.log --max-total 100
+//! ```
+//!
+//! ### For long lines -> (--max-columns N) or (--wrap):
+//!
+//! With `--max-columns`, only `N` columns of each line are shown, around its first match,
+//! and the cut parts are noted like `…[+123 chars]`. With `--wrap`, long lines are wrapped to the
+//! width of the terminal, under the start of the line.
+//!
+//! ```bash
+//! lenz "error" dist/app.min.js --max-columns 120
+//! lenz "error" logs/app.json --wrap
+//! ```
+//!
+//! ### For showing only the matched parts of the lines -> (-o, --only-matching):
+//!
+//! ```bash
+//! lenz "ID-" /path/to/your/file.txt -o
+//! ```
+//!
+//! ### For choosing the output format -> (--format text|json) and coloring -> (--color auto|always|never):
+//!
+//! With `--format json`, each result is printed as one JSON object per line.
+//! By default, the output is only colored when it is a terminal and `NO_COLOR` is not set.
+//!
+//! ```bash
+//! lenz "query" /path/to/your/file.txt --format json
+//! lenz "query" /path/to/your/file.txt --color always | less -R
+//! ```
+//!
+//! ### For choosing the colors -> (--theme default|light|colorblind) and changing them -> (--colors SPEC):
+//!
+//! Each part of the output is a slot of the theme: `match`, `lineno`, `matched-lineno`, `path`,
+//! `count`, `separator`, `error`, and `removed` and `added` for the `-` and `+` of `--diff`. A slot is changed with `SLOT:fg:COLOR`, `SLOT:bg:COLOR`,
+//! `SLOT:style:ATTR` (`bold`, `italic`, `underline`, or `nobold` and so on) or `SLOT:none`,
+//! or all at once with `SLOT:STYLE`, like `match:bold+underline+fg=#ff8800+bg=236`.
+//! A color is a name, an index of the 256 colors or `#RRGGBB`; colors the terminal cannot display
+//! (going by `COLORTERM` and `TERM`) are replaced by the nearest ones it can.
+//!
+//! ```bash
+//! lenz "query" /path/to/your/file.txt --theme light --colors 'match:bg:yellow' --colors 'match:style:bold'
+//! lenz "query" /path/to/your/file.txt --colors 'match:bold+fg=#1e1e1e+bg=#ffaf00'
+//! ```
+//!
+//! ### For clickable paths -> (--hyperlink-format default|vscode|cursor|idea|TEMPLATE):
+//!
+//! In terminals that support `OSC 8` hyperlinks, the file names are linked to their files.
+//! A template can use `{path}`, `{line}`, `{col}` and `{host}`; if it has a `{line}`, the line
+//! numbers are linked too. Nothing is linked when the output is not a terminal.
+//!
+//! ```bash
+//! lenz "TODO" src/*.rs --hyperlink-format 'vscode://file{path}:{line}:{col}'
+//! ```
+//!
+//! ### For editors -> (--vimgrep):
+//!
+//! Every match is printed as an uncolored `path:line:col:text` row, which can be loaded
+//! into vim's quickfix list, Emacs' grep-mode or a VS Code problem matcher.
+//!
+//! ```bash
+//! vim -q <(lenz "TODO" src/*.rs --vimgrep)
+//! ```
+//!
+//! ### For pasting into issues and wikis -> (--format markdown):
+//!
+//! Each file gets a heading and a fenced block of its cells (every line in page view),
+//! followed by a summary table and an "Errors" section.
+//!
+//! ```bash
+//! lenz "panic!" src/*.rs --format markdown > results.md
+//! ```
+//!
+//! ### For spreadsheets -> (--format csv|tsv) with (--columns path,line,column,offset,match,text,query):
+//!
+//! Each match is a record under a header naming the columns. The default columns are `path,line,column,match`.
+//!
+//! ```bash
+//! lenz "error" app.log --format csv --columns line,offset,text > errors.csv
+//! ```
+//!
+//! ### For code-scanning in CI -> (--format sarif):
+//!
+//! A single SARIF 2.1.0 document is printed, where the query is the rule and each match is a result.
+//!
+//! ```bash
+//! lenz "password" src/*.rs --format sarif > lenz.sarif
+//! ```
+//!
+//! ### For sharing the results as a single HTML file -> (--html report.html):
+//!
+//! Each file gets a collapsible section with the same cells as the terminal, followed by the errors report.
+//!
+//! ```bash
+//! lenz "query" /path/to/your/file.txt -p --html report.html
+//! ```
+//!
+//! ### For following files as they grow -> (-f, --follow):
+//!
+//! ```bash
+//! lenz "ERROR" /var/log/app.log -f
+//! ```
+//!
+//! ### For searching again whenever the files change -> (-w, --watch):
+//!
+//! Directories are expanded to the files they contain.
+//! With `--diff`, only the matches added or removed since the previous run are shown.
+//!
+//! ```bash
+//! lenz "TODO" src/ -w --diff
+//! ```
+//!
+//! ### For searching only a part of a file -> (--lines FIRST:LAST, --bytes OFFSET:LEN):
+//!
+//! Line numbers in the output stay the true line numbers of the file.
+//!
+//! ```bash
+//! lenz "query" /path/to/a/huge/file.log --lines 1200:1800
+//! lenz "query" /path/to/a/huge/file.log --bytes 1048576:4096
+//! ```
+//!
+//! ### For setting your own defaults -> (config files) and showing them -> (--print-config):
+//!
+//! The settings of `$XDG_CONFIG_HOME/lenz/config.toml` (the user's) and of the nearest `.lenz.toml`
+//! (the project's) are applied before the options, which always win. If `LENZ_CONFIG` is set,
+//! only the file it names is read. The keys are `case`, `format`, `color`, `theme`, `colors`,
+//! `hyperlink-format`, `exclude`, `context`, `columns`, `max-count`, `max-total`, `max-columns`, `wrap`,
+//! `tab-width`, `column`, `byte-offset`, `only-matching`, `null` and `mmap`.
+//!
+//! ```toml
+//! # .lenz.toml
+//! case = "smart"
+//! context = 1
+//! exclude = ["target", "*.min.js"]
+//! ```
+//!
+//! ```bash
+//! lenz --print-config
+//! ```
+//!
 //! ### You can use (-h, --help) for getting help about `lenz`:
 //!
 //! ```bash
@@ -47,6 +244,25 @@
 //! lenz --version
 //! ```
 //!
+//! ## Using `lenz` as a library:
+//!
+//! A `Searcher` is built with a `SearcherBuilder` and returns the matches instead of displaying them:
+//!
+//! ```
+//! use lenz::searcher::{ CaseMode, SearcherBuilder, };
+//!
+//! let searcher = SearcherBuilder::new("todo").case_mode(CaseMode::Smart).word(true).build();
+//! for m in searcher.search_reader("notes", "TODO: docs\ntodos".as_bytes()) {
+//!     let m = m.unwrap();
+//!     println!("{}:{}: {:?}", m.path, m.line_number, m.spans);
+//! }
+//! ```
+//!
+//! The printers, the cells and the pager are each given a `style::Look` when they are built,
+//! which holds the colors, the theme, the tab width and the hyperlinks of their output.
+//! `run_with` runs `lenz` like the command line does, writing to the given `out` and `err`
+//! instead of the standard output and error.
+//!
 //! *GitHub repo: <https://github.com/mimseyedi/lenz>*
 
 
@@ -56,20 +272,71 @@ pub mod help;
 pub mod cells;
 pub mod style;
 pub mod errors;
+pub mod opts;
+pub mod mmap;
+pub mod matcher;
+pub mod follow;
+pub mod watch;
+pub mod range;
+pub mod width;
+pub mod json;
+pub mod sarif;
+pub mod html;
+pub mod markdown;
+pub mod csv;
+pub mod printer;
+pub mod searcher;
+pub mod config;
+pub mod glob;
+pub mod theme;
+pub mod hyperlink;
+pub mod pager;
 
 // Interiors
 use std::fs::{ File, } ;
 use std::path::{ Path, };
-use std::io::{BufReader, Error};
-use std::process::exit;
+use std::io::{ self, stdout, BufReader, Error, ErrorKind, IsTerminal, Write, };
+use std::process::{ ExitCode, };
 // crate
-use crate::style::{ ANSIStyle, };
-use crate::errors::{ ErrorMsg, };
+use crate::errors::{ LenzError, };
+use crate::follow::{ FollowedFile, };
 use crate::rf::{ ReadableFile, };
+use crate::opts::{ Options, Action, Format, Mode, };
+use crate::matcher::{ Matcher, };
+use crate::printer::{ Printer, };
+use crate::searcher::{ Searcher, SearcherBuilder, };
+use crate::style::{ ColorDepth, Look, };
+use crate::hyperlink::{ PathCache, };
+use crate::theme::{ Theme, };
+
+
+/// How a search ended, which decides the exit code of `run`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Outcome {
+    /// Whether something was found (with `-L`, whether a file without a match was found).
+    pub matched: bool,
+    /// Whether a file could not be read, or the output could not be written.
+    pub failed: bool,
+}
+
+
+impl Outcome {
+    /// This method returns the exit code of the search: `0` if something was found, `1` if nothing was,
+    /// and `2` if there was an error, as `grep` does.
+    pub fn exit_code(&self) -> ExitCode {
+        match (self.failed, self.matched) {
+            (true, _) => ExitCode::from(2),
+            (false, true) => ExitCode::SUCCESS,
+            (false, false) => ExitCode::FAILURE,
+        }
+    }
+}
 
 
 /// The task of this function is to count a substring (query) in a string.
 /// The search can be performed case-sensitively or case-insensitively.
+/// This compiles a new `Matcher` on every call; for repeated searches,
+/// build a `Matcher` once and use `Matcher::count` instead.
 ///
 /// # Arguments
 ///
@@ -83,20 +350,15 @@ use crate::rf::{ ReadableFile, };
 /// where `count` is the total number of occurrences. Returns `None` if the query
 /// is not found.
 pub fn count_query(query: &str, text: &str, ignore_case: bool) -> Option<usize> {
-    let mut t = text.to_string();
-    let mut q = query.to_string();
-    if ignore_case {
-        t = text.to_lowercase();
-        q = q.to_lowercase();
-    }
-    let c: usize = t.matches(&q).count();
-    if c > 0 { Some(c) } else { None }
+    Matcher::new(query, ignore_case).count(text)
 }
 
 
 /// The task of this function is to find the index of the beginning and end
 /// of each specified substring (query) in a string.
 /// The search can be performed case-sensitively or case-insensitively.
+/// This compiles a new `Matcher` on every call; for repeated searches,
+/// build a `Matcher` once and use `Matcher::find_iter` instead.
 ///
 /// # Arguments
 ///
@@ -107,20 +369,9 @@ pub fn count_query(query: &str, text: &str, ignore_case: bool) -> Option<usize>
 /// # Returns
 ///
 /// An `Option<Vec<(usize, usize)`. If the substring exists in the string, a `Some(Vec)`
-/// consisting of the start and end indices is returned, otherwise `None`.
+/// consisting of the start indices and lengths is returned, otherwise `None`.
 pub fn find_all(query: &str, text: &str, ignore_case: bool) -> Option<Vec<(usize, usize)>> {
-    let (t, q);
-    let matches: Vec<(usize, &str)>;
-    if ignore_case {
-        t = text.to_lowercase();
-        q = query.to_lowercase();
-        matches = t.match_indices(&q).collect();
-    } else {
-        matches = text.match_indices(query).collect();
-    }
-    let vec: Vec<(usize, usize)> = matches.into_iter()
-                                          .map(|(x, m)| (x, m.len()))
-                                          .collect();
+    let vec: Vec<(usize, usize)> = Matcher::new(query, ignore_case).find_iter(text).collect();
     if !vec.is_empty() { Some(vec) } else { None }
 }
 
@@ -150,26 +401,68 @@ pub fn read_file(path: &str) -> Result<BufReader<File>, Error> {
 
 
 /// The task of this function is to display the **contents(matches)** of each file
-/// using the **methods** implemented for and accessed by `ReadableFiles`.
+/// by sending them to a `Printer`, using the **methods** implemented for and accessed by `ReadableFiles`.
 ///
 /// # Arguments
 ///
-/// * `files` - A **vector** containing `ReadableFiles` to display.
-/// * `mode` - Display mode -> `0` for counting, `1` for cell display, `2` for page-based display.
+/// * `files` - The `ReadableFiles` to display.
+/// * `opts` - The options entered by the user.
+/// * `printer` - Where the results are sent.
 ///
-/// # Panics
+/// # Returns
+///
+/// `true` if some matched lines were left out because of `--max-count` or `--max-total`,
+/// along with the `Outcome` of the files.
+///
+/// # Errors
 ///
-/// If the display mode is set incorrectly,
-/// an uncontrollable error (panic) will be thrown.
-fn show(files: Vec<ReadableFile>, mode: usize) {
+/// If the printer fails to write.
+fn show(files: &[ReadableFile<'_>], opts: &Options, printer: &mut dyn Printer) -> Result<(bool, Outcome), Error> {
+    let mut remaining = opts.max_total;
+    let mut truncated = false;
+    let mut outcome = Outcome::default();
     for file in files {
-        match mode {
-            0 => file.print_count(),
-            1 => file.print_cells(),
-            2 => file.print_page(),
-            _ => panic!("Invalid mode! You must select between (0, 1, 2)."),
+        // Listing files is not limited.
+        if opts.mode.lists() {
+            let shown = file.search(None, printer)?;
+            outcome.failed |= shown.failed;
+            outcome.matched |= !shown.failed && shown.matched == (opts.mode == Mode::FilesWithMatches);
+            continue;
+        }
+        if remaining == Some(0) {
+            // The rest of the files are only checked for a first match.
+            if file.has_match().is_ok_and(|found| found) {
+                truncated = true;
+                break;
+            }
+            continue;
         }
+        let limit = match (opts.max_count, remaining) {
+            (Some(m), Some(r)) => Some(m.min(r)),
+            (m, r) => m.or(r),
+        };
+        let shown = file.search(limit, printer)?;
+        truncated |= shown.truncated;
+        outcome.matched |= shown.matched;
+        outcome.failed |= shown.failed;
+        if let Some(r) = remaining.as_mut() {
+            *r -= shown.lines;
+        }
+    }
+    Ok((truncated, outcome))
+}
+
+
+/// This function returns the limits given by `--max-count` and `--max-total`, in words.
+fn get_truncated_limits(opts: &Options) -> String {
+    let mut limits = Vec::new();
+    if let Some(m) = opts.max_count {
+        limits.push(format!("at most {} matched line(s) per file", m));
+    }
+    if let Some(t) = opts.max_total {
+        limits.push(format!("at most {} matched line(s) in total", t));
     }
+    limits.join(", ")
 }
 
 
@@ -182,35 +475,15 @@ fn show(files: Vec<ReadableFile>, mode: usize) {
 ///
 /// # Returns
 ///
-/// A `Result<String, ErrorMsg>`, which returns an `Ok(file)`
-/// for confirmation and an `Err(ErrorMsg)` for an error.
-fn check_file(file: &str) -> Result<String, ErrorMsg> {
+/// A `Result<String, LenzError>`, which returns an `Ok(file)`
+/// for confirmation and an `Err(LenzError)` for an error.
+fn check_file(file: &str) -> Result<String, LenzError> {
     let f_path = Path::new(file);
     if !f_path.exists() {
-        return Err(
-            ErrorMsg::new(
-                format!(
-                    "File {}{}'{}'{} does not exist.",
-                    ANSIStyle::Italic.as_str(),
-                    ANSIStyle::FGWhite.as_str(),
-                    file,
-                    ANSIStyle::Reset.as_str(),
-                )
-            )
-        )
+        return Err(LenzError::NotFound { path: file.to_string() })
     }
     if !f_path.is_file() {
-        return Err(
-            ErrorMsg::new(
-                format!(
-                    "Path {}{}'{}'{} is not a file.",
-                    ANSIStyle::Italic.as_str(),
-                    ANSIStyle::FGWhite.as_str(),
-                    file,
-                    ANSIStyle::Reset.as_str(),
-                )
-            )
-        )
+        return Err(LenzError::NotAFile { path: file.to_string() })
     }
     Ok(file.to_string())
 }
@@ -222,65 +495,109 @@ fn check_file(file: &str) -> Result<String, ErrorMsg> {
 /// # Arguments
 ///
 /// * `file` - The path to the file to be searched.
-/// * `query` - The string to search for.
-/// * `ignore_case` - A boolean indicating whether the search should be case-insensitive.
+/// * `opts` - The options entered by the user.
+/// * `searcher` - The compiled search, shared by all files.
+/// * `look` - How the cells of the file are styled.
 ///
 /// # Returns
 ///
-/// A `Result<ReadableFile, ErrorMsg>`, which returns an `Ok(ReadableFile)`
-/// for confirmation and an `Err(ErrorMsg)` for an error.
-fn create_rf<'a>(file: &'a str, query: &'a str, ignore_case: bool) -> Result<ReadableFile, ErrorMsg> {
-    match check_file(file) {
-        Ok(f) => {
-            let rf = ReadableFile::new(
-                f,
-                query.to_string(),
-                ignore_case,
-            );
-            Ok(rf)
-        },
-        Err(e) => Err(e)
-    }
+/// A `Result<ReadableFile, LenzError>`, which returns an `Ok(ReadableFile)`
+/// for confirmation and an `Err(LenzError)` for an error.
+fn create_rf<'a>(file: &str, opts: &'a Options, searcher: &'a Searcher, look: &'a Look) -> Result<ReadableFile<'a>, LenzError> {
+    let f = check_file(file)?;
+    let mmap = mmap::use_mmap(&f, opts.mmap);
+    Ok(
+        ReadableFile::new(
+            f,
+            searcher,
+            opts,
+            look,
+            mmap,
+        )
+    )
 }
 
 
-/// This function is responsible for reporting files that had problems
-/// and could not be opened and were thrown into the trash.
-/// At the end of each output, a report of the corrupted files will be given.
+/// The task of this function is to search the files once and display the output,
+/// followed by the report of the corrupted files.
 ///
 /// # Arguments
 ///
-/// * `gr` - A referenced to a vector of errors (`&Vec<ErrorMsg>`) that should be checked and displayed.
-fn garbage_report(gr: &Vec<ErrorMsg>) {
-    let gr_len = gr.len();
-    println!("{}",
-        format!(
-            "\n{}{}* Errors Report: {}",
-            ANSIStyle::Bold.as_str(),
-            ANSIStyle::FGRed.as_str(),
-            ANSIStyle::Reset.as_str(),
-        )
-    );
-    let mut br;
-    for (i, e) in gr.iter().enumerate() {
-        if gr_len == 1 {
-            br = "└──";
-        } else {
-            if i == gr_len - 1 {
-                br = "└──";
-            } else {
-                br = "├──";
-            }
+/// * `files` - The paths of the files to be searched.
+/// * `opts` - The options entered by the user.
+/// * `searcher` - The compiled search.
+/// * `look` - How the output is styled.
+///
+/// # Returns
+///
+/// The paths of the files that could be searched along with where their search read up to
+/// (see `ReadableFile::reached`), and the `Outcome` of the search.
+///
+/// # Errors
+///
+/// If the printer fails to write, unless the output was closed (like `| head`).
+fn search(files: &[String], opts: &Options, searcher: &Searcher, look: &Look, printer: &mut dyn Printer) -> Result<(Vec<FollowedFile>, Outcome), Error> {
+    let mut gr: Vec<LenzError> = Vec::new();
+    let mut output: Vec<ReadableFile> = Vec::new();
+    for file in files.iter().filter(|f| !glob::is_excluded(&opts.exclude, f)) {
+        match create_rf(file, opts, searcher, look) {
+            Ok(f) => output.push(f),
+            Err(e) => gr.push(e),
         }
-        let e_msg = e.msg();
-        println!(
-            "{}{}[{}]{} {}",
-            ANSIStyle::FGRed.as_str(),
-            br,
-            i + 1,
-            ANSIStyle::Reset.as_str(),
-            e_msg,
-        );
+    }
+    let result = printer.begin().and_then(|_| show(&output, opts, printer)).and_then(|(truncated, outcome)| {
+        for e in &gr {
+            printer.error(e)?;
+        }
+        let limits = get_truncated_limits(opts);
+        printer.finish(if truncated { Some(&limits) } else { None })?;
+        Ok(Outcome { failed: outcome.failed || !gr.is_empty(), ..outcome })
+    });
+    let outcome = match result {
+        Ok(outcome) => outcome,
+        // A closed output (like `| head`) quietly ends the search.
+        Err(e) if e.kind() == ErrorKind::BrokenPipe => Outcome { matched: true, failed: false },
+        Err(e) => return Err(e),
+    };
+    let paths = output.iter().map(|f| (f.path().to_string(), f.reached())).collect();
+    Ok((paths, outcome))
+}
+
+
+/// This function returns the `Searcher` described by the options entered by the user.
+/// The limits of `-m` and `--max-total` are left to `ReadableFile::search`,
+/// which keeps reading past them to tell whether matched lines were left out.
+fn get_searcher(opts: &Options) -> Searcher {
+    SearcherBuilder::new(&opts.query)
+        .case_mode(opts.case)
+        .selection(opts.selection)
+        .build()
+}
+
+
+/// This function returns the `Theme` described by the options entered by the user:
+/// the built-in theme, with the changes of `--colors` applied in order.
+fn get_theme(opts: &Options) -> Theme {
+    let mut theme = Theme::named(&opts.theme).unwrap_or(Theme::DEFAULT);
+    for spec in &opts.colors {
+        theme.apply(spec);
+    }
+    theme
+}
+
+
+/// This function returns the `Look` described by the options entered by the user and by the terminal:
+/// only text is styled (when `--color` allows it), and only a terminal gets hyperlinks.
+fn get_look(opts: &Options) -> Look {
+    // Hyperlinks only make sense in a terminal that shows the text.
+    let links = opts.format == Format::Text && stdout().is_terminal();
+    Look {
+        colors: opts.format == Format::Text && opts.color.enabled(),
+        depth: ColorDepth::detect(),
+        theme: get_theme(opts),
+        tab_width: opts.tab_width.max(1),
+        hyperlink_format: if links { opts.hyperlink_format.clone() } else { String::new() },
+        paths: PathCache::default(),
     }
 }
 
@@ -296,103 +613,59 @@ fn garbage_report(gr: &Vec<ErrorMsg>) {
 ///
 /// # Returns
 ///
-/// This function does not return anything and is just an executor.
-pub fn run(args: Vec<String>) {
-    let mut mode = 1;
-    let alen = args.len();
-    if alen == 1 && args[0].starts_with("-") {
-        let opt = &args[0];
-        match opt.to_string().as_str() {
-            "-h" | "--help" => {
-                help::get_help().show();
-                exit(0);
-            },
-            "-v" | "--version" => {
-                help::print_version();
-                exit(0);
-            },
-            _ => {
-                let msg = String::from(
-                    "Only two options '-h, --help' and '-v, --version' are accepted without any arguments."
-                );
-                ErrorMsg::new(msg).raise();
-                exit(1);
-            }
-        }
-    }
-    if alen < 2 {
-        let msg = String::from(
-            "At least two arguments are expected."
-        );
-        ErrorMsg::new(msg).raise();
-        exit(1);
-    }
-    let mut gr: Vec<ErrorMsg> = Vec::new();
-    let query = &args[0];
-    let files;
-    let mut opt = "";
-    if args[alen - 1].starts_with("-") {
-        opt = &args[alen - 1];
-        files = &args[1..alen - 1];
-    } else {
-        files = &args[1..];
-    }
+/// The `ExitCode` the program should end with; it is up to the caller to end it.
+/// A search ends with `0` if something was found, `1` if nothing was, and `2` if there was an error
+/// (including a command line that is not valid).
+pub fn run(args: Vec<String>) -> ExitCode {
+    run_with(args, &mut io::stdout(), &mut io::stderr())
+}
 
-    let mut output: Vec<ReadableFile> = Vec::new();
 
-    if opt.is_empty() {
-        for file in files {
-            match create_rf(file, query, false) {
-                Ok(f) => output.push(f),
-                Err(e) => gr.push(e),
-            }
-        }
-    } else {
-        match opt {
-            "-c" | "--count" => {
-                for file in files {
-                    match create_rf(file, query, false) {
-                        Ok(f) => output.push(f),
-                        Err(e) => gr.push(e),
-                    }
-                }
-                mode = 0;
-            },
-            "-p" | "--page-view" => {
-                for file in files {
-                    match create_rf(file, query, false) {
-                        Ok(f) => output.push(f),
-                        Err(e) => gr.push(e),
-                    }
-                }
-                mode = 2;
-            },
-            "-i" | "--ignore-case" => {
-                for file in files {
-                    match create_rf(file, query, true) {
-                        Ok(f) => output.push(f),
-                        Err(e) => gr.push(e),
-                    }
-                }
-                mode = 1;
-            },
-            _ => {
-                ErrorMsg::new(
-                    format!(
-                        "This option is not valid: {}{}'{}'{}.",
-                        ANSIStyle::Italic.as_str(),
-                        ANSIStyle::FGWhite.as_str(),
-                        opt,
-                        ANSIStyle::Reset.as_str(),
-                    )
-                ).raise();
-                exit(1);
-            }
-        }
+/// This function is like `run`, but the output is written to `out` and the errors to `err`.
+/// The interactive pager still draws on the terminal.
+pub fn run_with(args: Vec<String>, out: &mut dyn Write, err: &mut dyn Write) -> ExitCode {
+    let parsed = config::load().and_then(|settings| opts::parse_args_with_config(&args, &settings));
+    let written = match parsed {
+        Ok(Action::Search(opts)) => return run_search(&opts, out, err).exit_code(),
+        Ok(Action::PrintConfig(report)) => write!(out, "{}", report),
+        Ok(Action::Help) => help::get_help().write_to(out),
+        Ok(Action::Version) => writeln!(out, "{}", help::get_version()),
+        Err(e) => {
+            let _ = e.write_to(err, &Look::for_stderr(opts::get_color_choice(&args)));
+            return Outcome { matched: false, failed: true }.exit_code();
+        },
+    };
+    Outcome { matched: true, failed: written.is_err() }.exit_code()
+}
+
+
+/// The task of this function is to run the search asked for by the options, in watch mode,
+/// in the pager or once (then following the files if asked to).
+///
+/// # Returns
+///
+/// The `Outcome` of the search. A failure to write the output is reported to `err`.
+fn run_search(opts: &Options, out: &mut dyn Write, err: &mut dyn Write) -> Outcome {
+    let look = get_look(opts);
+    let searcher = get_searcher(opts);
+    if opts.watch {
+        watch::watch(opts, &searcher, &look, out, err);
     }
-    show(output, mode);
-    if !gr.is_empty() {
-        garbage_report(&gr);
+    let searched = if pager::is_interactive(opts) {
+        pager::page(&opts.files, opts, &searcher, &look, printer::for_options(opts, &look, out, err).as_mut())
+              .map(|outcome| (Vec::new(), outcome))
+    } else {
+        search(&opts.files, opts, &searcher, &look, printer::for_options(opts, &look, out, err).as_mut())
+    };
+    let (paths, outcome) = match searched {
+        Ok(searched) => searched,
+        Err(e) => {
+            let _ = writeln!(err, "{}", e);
+            return Outcome { matched: false, failed: true };
+        },
+    };
+    if opts.follow {
+        follow::follow(&paths, searcher.matcher(), opts, &look, printer::for_options(opts, &look, out, err).as_mut());
     }
-    exit(0);
-}
\ No newline at end of file
+    outcome
+}
@@ -9,9 +9,10 @@
 
 
 use std::env;
+use std::process::{ ExitCode, };
 
 
-fn main() {
+fn main() -> ExitCode {
     let args: Vec<String> = env::args().skip(1).collect();
-    lenz::run(args);
+    lenz::run(args)
 }
\ No newline at end of file
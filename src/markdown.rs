@@ -0,0 +1,174 @@
+//! This module is related to the **Markdown** output of `lenz` (`--format markdown`),
+//! which can be pasted into issues and wikis.
+//! Each file gets a heading with its number of matches and a fenced block with its cells,
+//! and the output ends with a table summarizing the files and an "Errors" section.
+
+
+use std::io::{ Error, Write, };
+use std::mem;
+use crate::cells::{ Cell, Gutter, VOID, };
+use crate::errors::{ LenzError, };
+use crate::printer::{ Printer, };
+use crate::rf::{ Shown, };
+use crate::width::{ padding, };
+
+
+/// The task of this function is to escape the characters of a text that have a meaning in Markdown.
+///
+/// # Arguments
+///
+/// * `text` - The text to be escaped.
+///
+/// # Returns
+///
+/// A new `String` that is displayed as it is.
+pub fn escape(text: &str) -> String {
+    let mut s = String::with_capacity(text.len());
+    for c in text.chars() {
+        if "\\`*_[]<>|#~".contains(c) {
+            s.push('\\');
+        }
+        s.push(c);
+    }
+    s
+}
+
+
+/// This function returns the heading of a file along with its number of matches.
+pub fn heading(path: &str, count: usize) -> String {
+    format!("## {} ({} match{})", escape(path), count, if count == 1 { "" } else { "es" })
+}
+
+
+/// The task of this function is to put lines into a fenced code block.
+/// The fence is made longer than any run of backticks in the lines, so it cannot be closed early.
+///
+/// # Arguments
+///
+/// * `lines` - The lines of the block.
+///
+/// # Returns
+///
+/// A `String` containing the whole block.
+pub fn fenced(lines: &[String]) -> String {
+    let longest = lines.iter().map(|l| {
+        l.split(|c| c != '`').map(str::len).max().unwrap_or(0)
+    }).max().unwrap_or(0);
+    let fence = "`".repeat((longest + 1).max(3));
+    format!("{}\n{}\n{}", fence, lines.join("\n"), fence)
+}
+
+
+/// This function returns a row of the summary table.
+pub fn row(path: &str, count: usize) -> String {
+    format!("| {} | {} |", escape(path), count)
+}
+
+
+/// This function returns the summary table made of the rows made by `row`.
+pub fn summary(rows: &[String]) -> String {
+    format!("## Summary\n\n| File | Matches |\n| --- | ---: |\n{}", rows.join("\n"))
+}
+
+
+/// The task of this function is to describe a line as a line of a Markdown code block.
+/// The position is followed by `:` for a matched line and by `-` for any other line.
+///
+/// # Arguments
+///
+/// * `cell` - The line.
+/// * `gutter` - What to display next to the line number.
+///
+/// # Returns
+///
+/// A `String` containing the line, without styling.
+pub fn line(cell: &Cell, gutter: Gutter) -> String {
+    let position = cell.get_position(gutter);
+    let void = padding(&position, VOID);
+    let sep = if cell.check_line() { ':' } else { '-' };
+    format!("{}{} {}{}", position, sep, void, cell.line())
+}
+
+
+/// This function returns the "Errors" section, numbered like the errors report of the terminal.
+pub fn errors(msgs: &[String]) -> String {
+    let items: Vec<String> = msgs.iter().enumerate()
+                                 .map(|(i, msg)| format!("{}. {}", i + 1, escape(msg)))
+                                 .collect();
+    format!("## Errors\n\n{}", items.join("\n"))
+}
+
+
+/// The `Printer` of the Markdown format.
+pub struct Markdown<W: Write> {
+    out: W,
+    gutter: Gutter,
+    /// The number of matches of the current file.
+    count: usize,
+    /// The lines of the current file.
+    lines: Vec<String>,
+    /// The rows of the summary table.
+    rows: Vec<String>,
+    errors: Vec<String>,
+}
+
+
+impl<W: Write> Markdown<W> {
+    /// `Markdown` Constructor.
+    pub fn new(out: W, gutter: Gutter) -> Self {
+        Self { out, gutter, count: 0, lines: Vec::new(), rows: Vec::new(), errors: Vec::new() }
+    }
+}
+
+
+impl<W: Write> Printer for Markdown<W> {
+    fn needs_count(&self) -> bool {
+        true
+    }
+
+    fn file_start(&mut self, _path: &str, count: Option<usize>) -> Result<(), Error> {
+        self.count = count.unwrap_or(0);
+        self.lines.clear();
+        Ok(())
+    }
+
+    fn matched(&mut self, _path: &str, cell: &Cell) -> Result<(), Error> {
+        self.lines.push(line(cell, self.gutter));
+        Ok(())
+    }
+
+    fn context(&mut self, _path: &str, cell: &Cell) -> Result<(), Error> {
+        self.lines.push(line(cell, self.gutter));
+        Ok(())
+    }
+
+    fn file_end(&mut self, path: &str, _shown: Shown) -> Result<(), Error> {
+        writeln!(self.out, "{}\n", heading(path, self.count))?;
+        if !self.lines.is_empty() {
+            writeln!(self.out, "{}\n", fenced(&self.lines))?;
+        }
+        self.rows.push(row(path, self.count));
+        Ok(())
+    }
+
+    fn error(&mut self, error: &LenzError) -> Result<(), Error> {
+        self.errors.push(error.plain());
+        Ok(())
+    }
+
+    fn finish(&mut self, truncated: Option<&str>) -> Result<(), Error> {
+        if let Some(limits) = truncated {
+            writeln!(self.out, "> Results were truncated: {}.\n", limits)?;
+        }
+        // While following files, only the errors are left to report.
+        let rows = mem::take(&mut self.rows);
+        if !rows.is_empty() {
+            writeln!(self.out, "{}", summary(&rows))?;
+        }
+        let errors = mem::take(&mut self.errors);
+        if !errors.is_empty() {
+            writeln!(self.out, "\n{}", self::errors(&errors))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,224 @@
+//! This module is related to **matching** the query in the text.
+//! The query is compiled into a `Matcher` once per run (lowered if needed,
+//! with a prebuilt substring finder) and then borrowed by everything that searches,
+//! so the hot path does not allocate for each line.
+
+
+use std::vec::{ IntoIter, };
+use memchr::{ memchr2, };
+use memchr::memmem::{ Finder, };
+
+
+/// A compiled query that can be used to search any number of texts.
+pub struct Matcher {
+    query: String,
+    ignore_case: bool,
+    /// Only match whole words: a match may not touch a letter, a digit or `_`.
+    word: bool,
+    /// The query as it is searched for, lowercased if `ignore_case` is set.
+    folded: String,
+    finder: Finder<'static>,
+}
+
+
+impl Matcher {
+    /// `Matcher` Constructor.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The string to search for.
+    /// * `ignore_case` - A boolean indicating whether the search should be case-insensitive.
+    pub fn new(query: &str, ignore_case: bool) -> Self {
+        Self::with_word(query, ignore_case, false)
+    }
+
+    /// `Matcher` Constructor, which can also restrict the matches to whole words.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The string to search for.
+    /// * `ignore_case` - A boolean indicating whether the search should be case-insensitive.
+    /// * `word` - A boolean indicating whether only whole words should match.
+    pub fn with_word(query: &str, ignore_case: bool, word: bool) -> Self {
+        let folded = if ignore_case { query.to_lowercase() } else { query.to_string() };
+        let finder = Finder::new(folded.as_bytes()).into_owned();
+        Self { query: query.to_string(), ignore_case, word, folded, finder }
+    }
+
+    /// `query` attr getter.
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// `ignore_case` attr getter.
+    pub fn ignore_case(&self) -> bool {
+        self.ignore_case
+    }
+
+    /// `word` attr getter.
+    pub fn word(&self) -> bool {
+        self.word
+    }
+
+    /// `finder` attr getter. The finder searches for the (lowered) query as raw bytes.
+    pub fn finder(&self) -> &Finder<'static> {
+        &self.finder
+    }
+
+    /// The task of this method is to find the start and length of each match in a text.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The string within which to search.
+    ///
+    /// # Returns
+    ///
+    /// An iterator of `(start, len)` pairs. Unless the search is case-insensitive
+    /// and the text is not ASCII, nothing is allocated.
+    pub fn find_iter<'m, 't>(&'m self, text: &'t str) -> Matches<'m, 't> {
+        let inner = if self.folded.is_empty() {
+            // An empty query matches at every char boundary, which only `str` knows about.
+            Inner::Folded(self.fold_matches(text))
+        } else if !self.ignore_case {
+            Inner::Exact { text: text.as_bytes(), finder: &self.finder, at: 0 }
+        } else if self.folded.is_ascii() && text.is_ascii() {
+            Inner::Ascii { text: text.as_bytes(), needle: self.folded.as_bytes(), at: 0 }
+        } else {
+            Inner::Folded(self.fold_matches(text))
+        };
+        Matches { inner, word: if self.word { Some(text) } else { None } }
+    }
+
+    /// The task of this method is to count the query in a text.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The string within which to search.
+    ///
+    /// # Returns
+    ///
+    /// `Some(count)` if the query is found at least once, otherwise `None`.
+    pub fn count(&self, text: &str) -> Option<usize> {
+        let c = self.find_iter(text).count();
+        if c > 0 { Some(c) } else { None }
+    }
+
+    /// This method returns `true` if the query exists in the text.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The string within which to search.
+    pub fn is_match(&self, text: &str) -> bool {
+        self.find_iter(text).next().is_some()
+    }
+
+    /// The slow path: the text is lowered into a new `String` and searched with `str`.
+    /// Lowering may change the length of a character (like `İ`), so the matches
+    /// are mapped back to the characters of the original text they were found in.
+    fn fold_matches(&self, text: &str) -> IntoIter<(usize, usize)> {
+        if !self.ignore_case {
+            return text.match_indices(&self.folded)
+                       .map(|(x, m)| (x, m.len()))
+                       .collect::<Vec<(usize, usize)>>()
+                       .into_iter();
+        }
+        let lowered = text.to_lowercase();
+        // The start and end of the original character each byte of `lowered` comes from.
+        // Only the lowering of `Σ` depends on its neighbours, and both of its forms have the same length.
+        let mut source: Vec<(usize, usize)> = Vec::with_capacity(lowered.len());
+        for (i, c) in text.char_indices() {
+            let len: usize = c.to_lowercase().map(char::len_utf8).sum();
+            source.extend(std::iter::repeat_n((i, i + c.len_utf8()), len));
+        }
+        let mut spans: Vec<(usize, usize)> = Vec::new();
+        let mut end = 0;
+        for (x, m) in lowered.match_indices(&self.folded) {
+            let (start, _) = source[x];
+            let (_, stop) = source[x + m.len() - 1];
+            // Two matches inside of the lowering of one character are the same character.
+            if start >= end {
+                spans.push((start, stop - start));
+                end = stop;
+            }
+        }
+        spans.into_iter()
+    }
+}
+
+
+/// An iterator over the `(start, len)` pairs of the matches in a text.
+pub struct Matches<'m, 't> {
+    inner: Inner<'m, 't>,
+    /// The text, if only whole words may match.
+    word: Option<&'t str>,
+}
+
+
+impl Matches<'_, '_> {
+    /// This method returns the next match of the query, whether it is a whole word or not.
+    fn next_any(&mut self) -> Option<(usize, usize)> {
+        match &mut self.inner {
+            Inner::Exact { text, finder, at } => {
+                let n = finder.needle().len();
+                let x = *at + finder.find(text.get(*at..)?)?;
+                *at = x + n;
+                Some((x, n))
+            },
+            Inner::Ascii { text, needle, at } => {
+                let n = needle.len();
+                let (lo, up) = (needle[0], needle[0].to_ascii_uppercase());
+                while *at + n <= text.len() {
+                    let x = *at + memchr2(lo, up, &text[*at..=text.len() - n])?;
+                    if text[x..x + n].eq_ignore_ascii_case(needle) {
+                        *at = x + n;
+                        return Some((x, n));
+                    }
+                    *at = x + 1;
+                }
+                None
+            },
+            Inner::Folded(iter) => iter.next(),
+        }
+    }
+
+    /// This method moves the search back to right after the start of a match that was not a whole word,
+    /// since a whole word may start inside of it.
+    fn retry_after(&mut self, x: usize) {
+        match &mut self.inner {
+            Inner::Exact { at, .. } | Inner::Ascii { at, .. } => *at = x + 1,
+            Inner::Folded(_) => {},
+        }
+    }
+}
+
+
+/// This function checks whether the match at `x` of length `n` is a whole word in the text.
+fn is_word(text: &str, x: usize, n: usize) -> bool {
+    let word_char = |c: char| c.is_alphanumeric() || c == '_';
+    let before = text.get(..x).and_then(|t| t.chars().next_back());
+    let after = text.get(x + n..).and_then(|t| t.chars().next());
+    !before.is_some_and(word_char) && !after.is_some_and(word_char)
+}
+
+
+enum Inner<'m, 't> {
+    Exact { text: &'t [u8], finder: &'m Finder<'static>, at: usize },
+    Ascii { text: &'t [u8], needle: &'m [u8], at: usize },
+    Folded(IntoIter<(usize, usize)>),
+}
+
+
+impl Iterator for Matches<'_, '_> {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Some(text) = self.word else { return self.next_any() };
+        loop {
+            let (x, n) = self.next_any()?;
+            if is_word(text, x, n) {
+                return Some((x, n));
+            }
+            self.retry_after(x);
+        }
+    }
+}
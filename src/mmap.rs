@@ -0,0 +1,208 @@
+//! This module is related to the **memory-mapped** search strategy.
+//! Large files are mapped into memory and scanned as a whole with a fast
+//! substring finder, instead of allocating a `String` for every line.
+//! Line boundaries are only looked up around the hits.
+
+
+use std::fs::{ File, };
+use std::io::{ Error, };
+use memchr::{ memchr, memrchr, memchr_iter, };
+use memchr::memmem::{ Finder, };
+use memmap2::{ Mmap, };
+use crate::matcher::{ Matcher, };
+
+
+/// Files of this size (in bytes) or larger are searched with the memory-mapped strategy
+/// unless the user chooses otherwise with `--mmap` or `--no-mmap`.
+pub const MMAP_THRESHOLD: u64 = 64 * 1024 * 1024;
+
+
+/// The task of this function is to decide whether a file should be memory-mapped.
+///
+/// # Arguments
+///
+/// * `path` - The path to the file.
+/// * `choice` - The user's choice, `None` if nothing was specified.
+///
+/// # Returns
+///
+/// `true` if the memory-mapped strategy should be used.
+pub fn use_mmap(path: &str, choice: Option<bool>) -> bool {
+    match choice {
+        Some(c) => c,
+        None => std::fs::metadata(path).is_ok_and(|m| m.len() >= MMAP_THRESHOLD),
+    }
+}
+
+
+/// The task of this function is to map a file into memory.
+///
+/// # Arguments
+///
+/// * `path` - The path to the file to be mapped.
+///
+/// # Returns
+///
+/// A `Result<Option<Mmap>, Error>`. Empty files cannot be mapped,
+/// so `Ok(None)` is returned for them.
+///
+/// # Errors
+///
+/// If there is a problem opening or mapping the file.
+pub fn map_file(path: &str) -> Result<Option<Mmap>, Error> {
+    map_open(&File::open(path)?)
+}
+
+
+/// The task of this function is to map a file that is already open into memory.
+///
+/// # Arguments
+///
+/// * `file` - The file to be mapped.
+///
+/// # Returns
+///
+/// A `Result<Option<Mmap>, Error>`, which is `Ok(None)` for an empty file.
+///
+/// # Errors
+///
+/// If there is a problem mapping the file.
+pub fn map_open(file: &File) -> Result<Option<Mmap>, Error> {
+    if file.metadata()?.len() == 0 {
+        return Ok(None);
+    }
+    // SAFETY: The map is read-only and only lives for the duration of a search.
+    // If another process truncates the file meanwhile, the behavior is undefined,
+    // which is the same trade-off every mmap-based search tool makes.
+    let map = unsafe { Mmap::map(file)? };
+    Ok(Some(map))
+}
+
+
+/// The task of this function is to count the query in a mapped file.
+///
+/// # Arguments
+///
+/// * `hay` - The contents of the file.
+/// * `matcher` - The compiled query.
+///
+/// # Returns
+///
+/// The number of matches.
+pub fn count(hay: &[u8], matcher: &Matcher) -> usize {
+    if scans_whole(matcher) {
+        return matcher.finder().find_iter(hay).count();
+    }
+    lines(hay).filter_map(|(_, l)| matcher.count(&String::from_utf8_lossy(l)))
+              .sum()
+}
+
+
+/// The task of this function is to find the lines of a mapped file that match the query.
+///
+/// # Arguments
+///
+/// * `hay` - The contents of the file.
+/// * `matcher` - The compiled query.
+///
+/// # Returns
+///
+/// An iterator of `(line number, line)` pairs. Lines do not contain their terminator.
+pub fn matched_lines<'a>(hay: &'a [u8], matcher: &'a Matcher) -> Box<dyn Iterator<Item = (usize, &'a [u8])> + 'a> {
+    if scans_whole(matcher) {
+        return Box::new(
+            HitLines {
+                hay,
+                finder: matcher.finder(),
+                at: 0,
+                counted: 0,
+                lineno: 1,
+            }
+        );
+    }
+    Box::new(
+        lines(hay).filter(move |(_, l)| {
+            matcher.is_match(&String::from_utf8_lossy(l))
+        })
+    )
+}
+
+
+/// The task of this function is to find the end of the last complete line of a mapped file,
+/// which is where following the file starts.
+///
+/// # Arguments
+///
+/// * `hay` - The contents of the file.
+///
+/// # Returns
+///
+/// The number of complete lines, and the byte offset after them.
+pub fn complete_lines(hay: &[u8]) -> (usize, u64) {
+    match memrchr(b'\n', hay) {
+        Some(p) => (memchr_iter(b'\n', &hay[..=p]).count(), p as u64 + 1),
+        None => (0, 0),
+    }
+}
+
+
+/// The task of this function is to split a mapped file into numbered lines,
+/// just like `BufRead::lines` does.
+///
+/// # Arguments
+///
+/// * `hay` - The contents of the file.
+///
+/// # Returns
+///
+/// An iterator of `(line number, line)` pairs.
+pub fn lines(hay: &[u8]) -> impl Iterator<Item = (usize, &[u8])> {
+    let body = hay.strip_suffix(b"\n").unwrap_or(hay);
+    let mut split = body.split(|&b| b == b'\n');
+    if hay.is_empty() {
+        split.next();
+    }
+    split.map(trim_cr).enumerate().map(|(i, l)| (i + 1, l))
+}
+
+
+/// The whole file can only be scanned at once when the match is byte-exact
+/// (not only whole words) and cannot cross line boundaries. Otherwise the search falls back to lines.
+fn scans_whole(matcher: &Matcher) -> bool {
+    !matcher.ignore_case() && !matcher.word() && !matcher.query().is_empty() && !matcher.query().contains('\n')
+}
+
+
+/// Removes a trailing carriage return from a line.
+fn trim_cr(line: &[u8]) -> &[u8] {
+    line.strip_suffix(b"\r").unwrap_or(line)
+}
+
+
+/// An iterator that jumps from hit to hit in a mapped file
+/// and only counts the newlines between them to know the line numbers.
+struct HitLines<'a> {
+    hay: &'a [u8],
+    finder: &'a Finder<'static>,
+    /// The offset at which the next search starts.
+    at: usize,
+    /// The offset up to which the newlines are counted.
+    counted: usize,
+    /// The line number at `counted`.
+    lineno: usize,
+}
+
+
+impl<'a> Iterator for HitLines<'a> {
+    type Item = (usize, &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let hit = self.at + self.finder.find(&self.hay[self.at..])?;
+        let start = memrchr(b'\n', &self.hay[..hit]).map_or(0, |p| p + 1);
+        let end = memchr(b'\n', &self.hay[hit..]).map_or(self.hay.len(), |p| hit + p);
+        self.lineno += memchr_iter(b'\n', &self.hay[self.counted..start]).count();
+        self.counted = start;
+        self.at = end;
+        Some((self.lineno, trim_cr(&self.hay[start..end])))
+    }
+}
@@ -0,0 +1,549 @@
+//! This module is related to parsing the **command-line arguments** of `lenz`.
+//! The arguments are turned into an `Options` structure that the rest
+//! of the program can use to decide how to search and display the files.
+
+
+use std::collections::{ HashMap, };
+use crate::hyperlink;
+use crate::config::{ self, Setting, };
+use crate::style::{ ColorChoice, };
+use crate::errors::{ LenzError, };
+use crate::range::{ Selection, };
+use crate::csv::{ Column, DEFAULT_COLUMNS, };
+use crate::searcher::{ CaseMode, };
+use crate::theme::{ ColorSpec, Theme, };
+use crate::width::{ TAB_WIDTH, };
+
+
+/// This structure holds everything the user asked for on the command line.
+pub struct Options {
+    /// The string to search for.
+    pub query: String,
+    /// The paths of the files to be searched.
+    pub files: Vec<String>,
+    /// How the results are displayed.
+    pub mode: Mode,
+    /// How the case of the letters is taken into account.
+    pub case: CaseMode,
+    /// Memory-mapped search strategy. `None` lets `lenz` choose by file size.
+    pub mmap: Option<bool>,
+    /// Keep the files open and display new matching lines as they are appended.
+    pub follow: bool,
+    /// Search again whenever the files change.
+    pub watch: bool,
+    /// With `watch`, only display the matches added or removed since the previous run.
+    pub diff: bool,
+    /// Only search this part of each file.
+    pub selection: Option<Selection>,
+    /// Display the column of the first match of each line.
+    pub column: bool,
+    /// Display the absolute byte offset of the first match of each line.
+    pub byte_offset: bool,
+    /// Display each match on its own row instead of the whole line.
+    pub only_matching: bool,
+    /// The format of the output.
+    pub format: Format,
+    /// When to color the output.
+    pub color: ColorChoice,
+    /// The name of the built-in theme the colors start from.
+    pub theme: String,
+    /// The changes made to the slots of the theme, in order.
+    pub colors: Vec<ColorSpec>,
+    /// The URL template of the hyperlinks of paths and line numbers. Empty when they are off.
+    pub hyperlink_format: String,
+    /// When listing files, end each path with a `NUL` byte instead of a newline.
+    pub null: bool,
+    /// The maximum number of matched lines to display for each file.
+    pub max_count: Option<usize>,
+    /// The maximum number of matched lines to display for all the files together.
+    pub max_total: Option<usize>,
+    /// Write the results to this HTML file instead of displaying them.
+    pub html: Option<String>,
+    /// The columns of the CSV and TSV formats.
+    pub columns: Vec<Column>,
+    /// The glob patterns of the files that should not be searched.
+    pub exclude: Vec<String>,
+    /// The number of lines to display around each matched line, in cell view.
+    pub context: usize,
+    /// The maximum number of columns of a line to display, around its first match.
+    pub max_columns: Option<usize>,
+    /// Wrap long lines to the width of the terminal.
+    pub wrap: bool,
+    /// The distance between two tab stops, when columns are counted.
+    pub tab_width: usize,
+}
+
+
+/// The format of the output, as chosen with `--format`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    /// The usual human-readable output.
+    Text,
+    /// One JSON object per line.
+    Json,
+    /// One `path:line:col:text` row per match, for editors.
+    Vimgrep,
+    /// A single SARIF 2.1.0 document, for code-scanning.
+    Sarif,
+    /// Markdown, for issues and wikis.
+    Markdown,
+    /// One comma-separated record per match, for spreadsheets.
+    Csv,
+    /// One tab-separated record per match, for spreadsheets.
+    Tsv,
+}
+
+
+impl Format {
+    /// The task of this method is to parse the value of `--format`.
+    ///
+    /// # Returns
+    ///
+    /// `Some(Format)` if the format is known, otherwise `None`.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "text" => Some(Format::Text),
+            "json" => Some(Format::Json),
+            "vimgrep" => Some(Format::Vimgrep),
+            "sarif" => Some(Format::Sarif),
+            "markdown" => Some(Format::Markdown),
+            "csv" => Some(Format::Csv),
+            "tsv" => Some(Format::Tsv),
+            _ => None,
+        }
+    }
+
+    /// This method returns the name of the format, as it is given to `--format`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Format::Text => "text",
+            Format::Json => "json",
+            Format::Vimgrep => "vimgrep",
+            Format::Sarif => "sarif",
+            Format::Markdown => "markdown",
+            Format::Csv => "csv",
+            Format::Tsv => "tsv",
+        }
+    }
+}
+
+
+/// The display mode, as chosen with `--count`, `--page-view`, `--files-with-matches`
+/// and `--files-without-match`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Mode {
+    /// Display the number of matched lines of each file.
+    Count,
+    /// Display each matched line in its own cell.
+    Cells,
+    /// Display every line of the files, with the matches highlighted.
+    Page,
+    /// Only display the paths of the files that match.
+    FilesWithMatches,
+    /// Only display the paths of the files that do not match.
+    FilesWithoutMatch,
+}
+
+
+impl Mode {
+    /// This method tells whether the mode only lists the paths of the files.
+    pub fn lists(&self) -> bool {
+        matches!(self, Mode::FilesWithMatches | Mode::FilesWithoutMatch)
+    }
+}
+
+
+/// The result of parsing the arguments.
+/// Either a search should be performed, or one of the informational options was requested.
+pub enum Action {
+    Help,
+    Version,
+    Search(Box<Options>),
+    /// The effective settings, along with where they come from (`--print-config`).
+    PrintConfig(String),
+}
+
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            query: String::new(),
+            files: Vec::new(),
+            mode: Mode::Cells,
+            case: CaseMode::Sensitive,
+            mmap: None,
+            follow: false,
+            watch: false,
+            diff: false,
+            selection: None,
+            column: false,
+            byte_offset: false,
+            only_matching: false,
+            format: Format::Text,
+            color: ColorChoice::Auto,
+            theme: String::from("default"),
+            colors: Vec::new(),
+            hyperlink_format: String::new(),
+            null: false,
+            max_count: None,
+            max_total: None,
+            html: None,
+            columns: DEFAULT_COLUMNS.to_vec(),
+            exclude: Vec::new(),
+            context: 0,
+            max_columns: None,
+            wrap: false,
+            tab_width: TAB_WIDTH,
+        }
+    }
+}
+
+
+/// This structure gathers the options as the arguments are read one by one.
+struct Parser {
+    opts: Options,
+    positional: Vec<String>,
+    only_positional: bool,
+    print_config: bool,
+    /// The settings that were given on the command line, by their config key.
+    keys: Vec<&'static str>,
+}
+
+
+impl Parser {
+    /// The task of this method is to read the arguments into the options.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(Some(Action))` if an informational option was found, which ends the parsing.
+    fn parse(&mut self, args: &[String]) -> Result<Option<Action>, LenzError> {
+        let opts = &mut self.opts;
+        let mut rest = args.iter();
+        while let Some(arg) = rest.next() {
+            if self.only_positional || !arg.starts_with('-') || arg == "-" {
+                self.positional.push(arg.to_string());
+                continue;
+            }
+            // Long options can also be given their value as `--name=value`.
+            let (name, mut inline) = match arg.split_once('=') {
+                Some((n, v)) if n.starts_with("--") => (n, Some(v.to_string())),
+                _ => (arg.as_str(), None),
+            };
+            match name {
+                "--" => self.only_positional = true,
+                "-h" | "--help" => return Ok(Some(Action::Help)),
+                "-v" | "--version" => return Ok(Some(Action::Version)),
+                "--print-config" => self.print_config = true,
+                "-c" | "--count" => opts.mode = Mode::Count,
+                "-p" | "--page-view" => opts.mode = Mode::Page,
+                "-l" | "--files-with-matches" => opts.mode = Mode::FilesWithMatches,
+                "-L" | "--files-without-match" => opts.mode = Mode::FilesWithoutMatch,
+                "-0" | "--null" => opts.null = true,
+                "-i" | "--ignore-case" => opts.case = CaseMode::Insensitive,
+                "-s" | "--case-sensitive" => opts.case = CaseMode::Sensitive,
+                "-S" | "--smart-case" => opts.case = CaseMode::Smart,
+                "--mmap" => opts.mmap = Some(true),
+                "--no-mmap" => opts.mmap = Some(false),
+                "-f" | "--follow" => opts.follow = true,
+                "-w" | "--watch" => opts.watch = true,
+                "--diff" => opts.diff = true,
+                "--column" => opts.column = true,
+                "-b" | "--byte-offset" => opts.byte_offset = true,
+                "-o" | "--only-matching" => opts.only_matching = true,
+                "--vimgrep" => opts.format = Format::Vimgrep,
+                "--format" => {
+                    let value = take_value(name, &mut inline, &mut rest)?;
+                    opts.format = Format::parse(&value).ok_or_else(|| invalid_value(name, &value))?;
+                },
+                "--color" => {
+                    let value = take_value(name, &mut inline, &mut rest)?;
+                    opts.color = ColorChoice::parse(&value).ok_or_else(|| invalid_value(name, &value))?;
+                },
+                "--theme" => {
+                    let value = take_value(name, &mut inline, &mut rest)?;
+                    Theme::named(&value).ok_or_else(|| invalid_value(name, &value))?;
+                    opts.theme = value;
+                },
+                "--colors" => {
+                    let value = take_value(name, &mut inline, &mut rest)?;
+                    opts.colors.push(ColorSpec::parse(&value).ok_or_else(|| invalid_value(name, &value))?);
+                },
+                "--hyperlink-format" => {
+                    let value = take_value(name, &mut inline, &mut rest)?;
+                    opts.hyperlink_format = hyperlink::parse_format(&value).ok_or_else(|| invalid_value(name, &value))?;
+                },
+                "-m" | "--max-count" => {
+                    let value = take_value(name, &mut inline, &mut rest)?;
+                    opts.max_count = Some(value.parse().map_err(|_| invalid_value(name, &value))?);
+                },
+                "--max-total" => {
+                    let value = take_value(name, &mut inline, &mut rest)?;
+                    opts.max_total = Some(value.parse().map_err(|_| invalid_value(name, &value))?);
+                },
+                "--max-columns" => {
+                    let value = take_value(name, &mut inline, &mut rest)?;
+                    opts.max_columns = Some(value.parse().ok().filter(|&n| n > 0).ok_or_else(|| invalid_value(name, &value))?);
+                },
+                "--wrap" => opts.wrap = true,
+                "--tab-width" => {
+                    let value = take_value(name, &mut inline, &mut rest)?;
+                    opts.tab_width = value.parse().ok().filter(|&n| n > 0).ok_or_else(|| invalid_value(name, &value))?;
+                },
+                "--exclude" => opts.exclude.push(take_value(name, &mut inline, &mut rest)?),
+                "-C" | "--context" => {
+                    let value = take_value(name, &mut inline, &mut rest)?;
+                    opts.context = value.parse().map_err(|_| invalid_value(name, &value))?;
+                },
+                "--html" => opts.html = Some(take_value(name, &mut inline, &mut rest)?),
+                "--columns" => {
+                    let value = take_value(name, &mut inline, &mut rest)?;
+                    opts.columns = Column::parse_list(&value).ok_or_else(|| invalid_value(name, &value))?;
+                },
+                "--lines" => {
+                    let value = take_value(name, &mut inline, &mut rest)?;
+                    opts.selection = Some(Selection::parse_lines(&value).ok_or_else(|| invalid_value(name, &value))?);
+                },
+                "--bytes" => {
+                    let value = take_value(name, &mut inline, &mut rest)?;
+                    opts.selection = Some(Selection::parse_bytes(&value).ok_or_else(|| invalid_value(name, &value))?);
+                },
+                _ => return Err(invalid_option(arg)),
+            }
+            if inline.is_some() {
+                return Err(invalid_option(arg));
+            }
+            self.keys.extend(config::key_of(name));
+        }
+        Ok(None)
+    }
+}
+
+
+/// The task of this function is to parse the arguments entered by the user.
+/// Options can be placed anywhere after the program name; the first argument
+/// that is not an option is the **query** and the rest are **files**.
+/// Everything after a bare `--` is treated as a query or a file.
+///
+/// # Arguments
+///
+/// * `args` - Environment arguments read from the terminal.
+///
+/// # Returns
+///
+/// A `Result<Action, LenzError>`, which returns an `Ok(Action)`
+/// for confirmation and an `Err(LenzError)` for an invalid command line.
+pub fn parse_args(args: &[String]) -> Result<Action, LenzError> {
+    parse_args_with_config(args, &[])
+}
+
+
+/// The task of this function is to find the `--color` asked for by the arguments,
+/// without parsing the rest of them, for the errors of a command line that is not valid.
+///
+/// # Returns
+///
+/// The last valid `--color`, or `ColorChoice::Auto`.
+pub fn get_color_choice(args: &[String]) -> ColorChoice {
+    let mut choice = ColorChoice::Auto;
+    let mut rest = args.iter().take_while(|arg| *arg != "--");
+    while let Some(arg) = rest.next() {
+        let value = match arg.strip_prefix("--color") {
+            Some("") => rest.next().map(|v| v.as_str()),
+            Some(inline) => inline.strip_prefix('='),
+            None => None,
+        };
+        if let Some(parsed) = value.and_then(ColorChoice::parse) {
+            choice = parsed;
+        }
+    }
+    choice
+}
+
+
+/// The task of this function is to parse the arguments entered by the user, like `parse_args`,
+/// on top of the settings of the config files. The arguments win over the settings,
+/// and later settings win over earlier ones.
+///
+/// # Arguments
+///
+/// * `args` - Environment arguments read from the terminal.
+/// * `settings` - The settings read from the config files, in order.
+///
+/// # Returns
+///
+/// A `Result<Action, LenzError>`, which returns an `Ok(Action)`
+/// for confirmation and an `Err(LenzError)` for an invalid command line or setting.
+pub fn parse_args_with_config(args: &[String], settings: &[Setting]) -> Result<Action, LenzError> {
+    let mut parser = Parser {
+        opts: Options::default(),
+        positional: Vec::new(),
+        only_positional: false,
+        print_config: false,
+        keys: Vec::new(),
+    };
+    let mut sources: HashMap<&'static str, String> = HashMap::new();
+    for setting in settings {
+        match parser.parse(&setting.to_args()?) {
+            Ok(None) => {},
+            Ok(Some(_)) => return Err(setting.error("This setting cannot be used in a config file.")),
+            Err(e) => return Err(setting.error(&e.plain())),
+        }
+        sources.insert(setting.key, setting.source());
+    }
+    parser.keys.clear();
+    if let Some(action) = parser.parse(args)? {
+        return Ok(action);
+    }
+    for key in &parser.keys {
+        sources.insert(key, String::from("command line"));
+    }
+    if parser.print_config {
+        return Ok(Action::PrintConfig(config::render(&parser.opts, &sources)));
+    }
+    let mut opts = parser.opts;
+    if parser.positional.len() < 2 {
+        return Err(
+            LenzError::Usage(
+                String::from("At least two arguments are expected.")
+            )
+        )
+    }
+    if opts.follow && opts.mode == Mode::Count {
+        return Err(
+            LenzError::Usage(
+                String::from("The '--follow' option cannot be used with '--count'.")
+            )
+        )
+    }
+    if opts.follow && opts.mode.lists() {
+        return Err(
+            LenzError::Usage(
+                String::from("The '--follow' option cannot be used when listing files.")
+            )
+        )
+    }
+    if opts.format == Format::Vimgrep && opts.mode != Mode::Cells {
+        return Err(
+            LenzError::Usage(
+                String::from("The '--vimgrep' option can only be used to display matched lines.")
+            )
+        )
+    }
+    if matches!(opts.format, Format::Csv | Format::Tsv) && opts.mode != Mode::Cells {
+        return Err(
+            LenzError::Usage(
+                String::from("The CSV and TSV formats can only be used to display matched lines.")
+            )
+        )
+    }
+    if opts.format == Format::Sarif && opts.mode != Mode::Cells {
+        return Err(
+            LenzError::Usage(
+                String::from("The SARIF format can only be used to display matched lines.")
+            )
+        )
+    }
+    if opts.format == Format::Sarif && (opts.follow || opts.watch) {
+        return Err(
+            LenzError::Usage(
+                String::from("The SARIF format cannot be used with '--follow' or '--watch'.")
+            )
+        )
+    }
+    if opts.html.is_some() && (opts.format != Format::Text || opts.mode.lists() || opts.follow || opts.watch) {
+        return Err(
+            LenzError::Usage(
+                String::from("The '--html' option cannot be used with other formats, '--follow', '--watch' or when listing files.")
+            )
+        )
+    }
+    if opts.format == Format::Markdown && opts.follow {
+        return Err(
+            LenzError::Usage(
+                String::from("The Markdown format cannot be used with '--follow'.")
+            )
+        )
+    }
+    if opts.watch && opts.follow {
+        return Err(
+            LenzError::Usage(
+                String::from("The '--watch' and '--follow' options cannot be used together.")
+            )
+        )
+    }
+    if opts.wrap && opts.max_columns.is_some() {
+        return Err(
+            LenzError::Usage(
+                String::from("The '--wrap' and '--max-columns' options cannot be used together.")
+            )
+        )
+    }
+    if opts.diff && !opts.watch {
+        return Err(
+            LenzError::Usage(
+                String::from("The '--diff' option can only be used with '--watch'.")
+            )
+        )
+    }
+    if opts.diff && opts.format != Format::Text {
+        return Err(
+            LenzError::Usage(
+                String::from("The '--diff' option can only be used with the text format.")
+            )
+        )
+    }
+    opts.query = parser.positional.remove(0);
+    opts.files = parser.positional;
+    Ok(Action::Search(Box::new(opts)))
+}
+
+
+/// The task of this function is to take the value of an option,
+/// either given inline (`--name=value`) or as the next argument (`--name value`).
+///
+/// # Arguments
+///
+/// * `name` - The name of the option.
+/// * `inline` - The inline value, which is taken if present.
+/// * `rest` - The remaining arguments.
+///
+/// # Returns
+///
+/// A `Result<String, LenzError>`, which is an `Err(LenzError)` if the value is missing.
+fn take_value<'a, I: Iterator<Item = &'a String>>(name: &str, inline: &mut Option<String>, rest: &mut I) -> Result<String, LenzError> {
+    if let Some(value) = inline.take() {
+        return Ok(value);
+    }
+    rest.next().map(|v| v.to_string()).ok_or_else(|| {
+        LenzError::MissingValue { option: name.to_string() }
+    })
+}
+
+
+/// This function generates the error for an option whose value is not valid.
+///
+/// # Arguments
+///
+/// * `name` - The name of the option.
+/// * `value` - The invalid value.
+///
+/// # Returns
+///
+/// An `LenzError` describing the invalid value.
+fn invalid_value(name: &str, value: &str) -> LenzError {
+    LenzError::InvalidValue { option: name.to_string(), value: value.to_string() }
+}
+
+
+/// This function generates the error for an option that `lenz` does not know.
+///
+/// # Arguments
+///
+/// * `opt` - The invalid option.
+///
+/// # Returns
+///
+/// An `LenzError` describing the invalid option.
+fn invalid_option(opt: &str) -> LenzError {
+    LenzError::InvalidOption { option: opt.to_string() }
+}
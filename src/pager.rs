@@ -0,0 +1,778 @@
+//! This module is related to the **interactive page view** of `lenz` (`-p, --page-view` in a terminal).
+//! When both the input and the output are a terminal, each file is shown one screen at a time
+//! instead of being dumped at once: it can be scrolled, the matches can be jumped between
+//! and the query can be changed while the file is open.
+//! A status bar at the bottom shows where the view is, like `match 3/57`.
+//!
+//! Otherwise (like `lenz "query" file -p | less`), page view displays the whole file as it always did.
+
+
+use std::io::{ self, BufRead, Error, IsTerminal, Read, Seek, SeekFrom, Write, };
+use crate::cells::{ Cell, Fit, Gutter, };
+use crate::errors::{ LenzError, };
+use crate::{ glob, read_file, Outcome, };
+use crate::matcher::{ Matcher, };
+use crate::opts::{ Format, Mode, Options, };
+use crate::printer::{ Printer, };
+use crate::searcher::{ CaseMode, Searcher, };
+use crate::style::{ ANSIStyle, Look, };
+use crate::width::{ self, Cursor, TAB_WIDTH, };
+
+
+/// The keys that are listed at the end of the status bar.
+const HINTS: &str = "n/N match  / query  g/G top/bottom  q quit";
+
+
+/// The number of lines that are indexed (or matched against a new query) at a time.
+const WINDOW: usize = 4096;
+
+
+/// The width of the column before the lines, where the current match is marked.
+const MARKER_WIDTH: usize = 1;
+
+
+/// A key pressed in the pager.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Key {
+    Char(char),
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    Home,
+    End,
+    Enter,
+    Backspace,
+    Esc,
+    /// `Ctrl-C`.
+    Interrupt,
+    /// A key the pager does not use.
+    Unknown,
+}
+
+
+impl Key {
+    /// The task of this function is to turn the bytes the terminal sent for a single key press into a `Key`.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - A character, a control byte or an escape sequence (like `ESC [ A` for the up arrow).
+    pub fn parse(bytes: &[u8]) -> Self {
+        match bytes {
+            [0x1b] => Key::Esc,
+            [0x1b, b'[' | b'O', b'A'] => Key::Up,
+            [0x1b, b'[' | b'O', b'B'] => Key::Down,
+            [0x1b, b'[' | b'O', b'H'] | [0x1b, b'[', b'1' | b'7', b'~'] => Key::Home,
+            [0x1b, b'[' | b'O', b'F'] | [0x1b, b'[', b'4' | b'8', b'~'] => Key::End,
+            [0x1b, b'[', b'5', b'~'] => Key::PageUp,
+            [0x1b, b'[', b'6', b'~'] => Key::PageDown,
+            [b'\r' | b'\n'] => Key::Enter,
+            [0x7f | 0x08] => Key::Backspace,
+            [0x03] => Key::Interrupt,
+            _ => {
+                let mut chars = std::str::from_utf8(bytes).unwrap_or_default().chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) if !c.is_control() => Key::Char(c),
+                    _ => Key::Unknown,
+                }
+            },
+        }
+    }
+}
+
+
+/// What the pager does after a key.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Flow {
+    /// The file stays open.
+    Continue,
+    /// The file is closed, and the next one (if any) is opened.
+    NextFile,
+    /// The pager is closed.
+    Quit,
+}
+
+
+/// A `Pager` holds a file that is viewed one screen at a time, along with where the view is.
+/// It does not talk to the terminal itself: it is given keys, and renders frames.
+///
+/// The file is never read as a whole. The byte offsets of its lines are indexed a window at a time,
+/// as far as the view (or a search for a match) needs them, and only the lines on the screen are read to be rendered.
+/// The lines are matched against the query as they are indexed, so the matches are always known
+/// for the beginning of the file and found further on when they are jumped to.
+pub struct Pager<R: BufRead + Seek> {
+    path: String,
+    reader: R,
+    matcher: Matcher,
+    case: CaseMode,
+    gutter: Gutter,
+    look: Look,
+    /// The number of lines before the first one, which are not paged through (like the ones before a `Selection`).
+    skipped: usize,
+    /// The byte offset where the lines stop being read, and the number of the last line that is read.
+    limit: (u64, usize),
+    /// The matcher of the search and its `max_count`: the lines stop right before the first matched line over it.
+    cutoff: Option<(Matcher, usize)>,
+    /// The number of lines that were matched by the `cutoff` matcher so far.
+    found: usize,
+    /// The byte offsets of the lines indexed so far.
+    offsets: Vec<u64>,
+    /// The byte offset after the last indexed line.
+    end: u64,
+    /// Whether the lines were indexed up to the end.
+    ended: bool,
+    /// The first error that happened while reading the file, after which nothing more is read.
+    failed: Option<Error>,
+    /// The number of indexed lines that were matched against the query.
+    checked: usize,
+    /// The indexes of the lines that match, among the checked ones.
+    matches: Vec<usize>,
+    /// The index of the first line on the screen.
+    top: usize,
+    /// The match the view is on (an index into `matches`), once one was jumped to.
+    current: Option<usize>,
+    /// The query being typed after `/`.
+    prompt: Option<String>,
+    /// The number of rows the lines are displayed on, which is the height of the screen without the status bar.
+    rows: usize,
+}
+
+
+impl<R: BufRead + Seek> Pager<R> {
+    /// `Pager` Constructor.
+    /// The view starts at the top of the file, on a screen of `24` rows until it is resized.
+    /// Nothing is read until the view needs it.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file.
+    /// * `reader` - The file, which is read from `start`.
+    /// * `start` - The number of lines and bytes before the first line that is paged through (see `Selection::seek`).
+    /// * `searcher` - The compiled search, whose query the lines are matched against and which limits the lines.
+    /// * `case` - How the case of a new query (typed after `/`) is treated.
+    /// * `gutter` - What to display next to the line numbers.
+    /// * `look` - How the lines and the status bar are styled.
+    pub fn new(path: &str, reader: R, start: (usize, u64), searcher: &Searcher, case: CaseMode, gutter: Gutter, look: Look) -> Self {
+        let copy = |m: &Matcher| Matcher::with_word(m.query(), m.ignore_case(), m.word());
+        let limit = match searcher.selection() {
+            Some(selection) => (start.1.saturating_add(selection.byte_limit()), selection.last_line()),
+            None => (u64::MAX, usize::MAX),
+        };
+        Self {
+            path: path.to_string(),
+            reader,
+            matcher: copy(searcher.matcher()),
+            case,
+            gutter,
+            look,
+            skipped: start.0,
+            limit,
+            cutoff: searcher.max_count().map(|max| (copy(searcher.matcher()), max)),
+            found: 0,
+            offsets: Vec::new(),
+            end: start.1,
+            ended: false,
+            failed: None,
+            checked: 0,
+            matches: Vec::new(),
+            top: 0,
+            current: None,
+            prompt: None,
+            rows: 23,
+        }
+    }
+
+    /// `top` attr getter: the index of the first line on the screen.
+    pub fn top(&self) -> usize {
+        self.top
+    }
+
+    /// `current` attr getter: the index of the match the view is on.
+    pub fn current(&self) -> Option<usize> {
+        self.current
+    }
+
+    /// This method returns the number of lines found to match the query so far.
+    pub fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    /// This method returns the number of lines indexed so far.
+    pub fn line_count(&self) -> usize {
+        self.offsets.len()
+    }
+
+    /// This method returns the query the lines are matched against.
+    pub fn query(&self) -> &str {
+        self.matcher.query()
+    }
+
+    /// This method returns whether every line was indexed and matched against the query,
+    /// so the counts of the status bar are final.
+    pub fn is_done(&self) -> bool {
+        self.failed.is_some() || (self.ended && self.checked == self.offsets.len())
+    }
+
+    /// This method takes the error that stopped the file from being read, if there was one.
+    pub fn take_error(&mut self) -> Option<Error> {
+        self.failed.take()
+    }
+
+    /// The task of this method is to fit the view into a screen of `height` rows,
+    /// one of which is taken by the status bar.
+    pub fn resize(&mut self, height: usize) {
+        self.rows = height.saturating_sub(1).max(1);
+        self.index_to(self.top + self.rows);
+        self.top = self.top.min(self.max_top());
+    }
+
+    /// The task of this method is to act on a key pressed by the user.
+    ///
+    /// # Returns
+    ///
+    /// Whether the file stays open, or what is closed.
+    pub fn handle(&mut self, key: Key) -> Flow {
+        if let Some(prompt) = self.prompt.as_mut() {
+            match key {
+                Key::Char(c) => prompt.push(c),
+                Key::Backspace => {
+                    prompt.pop();
+                },
+                Key::Enter => {
+                    let query = self.prompt.take().unwrap_or_default();
+                    if !query.is_empty() {
+                        self.set_query(&query);
+                    }
+                },
+                Key::Esc | Key::Interrupt => self.prompt = None,
+                _ => {},
+            }
+            return Flow::Continue;
+        }
+        match key {
+            Key::Char('q') => return Flow::NextFile,
+            Key::Char('Q') | Key::Interrupt => return Flow::Quit,
+            Key::Char('j') | Key::Down | Key::Enter => self.scroll_by(1),
+            Key::Char('k') | Key::Up => self.scroll_by(-1),
+            Key::Char(' ' | 'f') | Key::PageDown => self.scroll_by(self.rows as isize),
+            Key::Char('b') | Key::PageUp => self.scroll_by(-(self.rows as isize)),
+            Key::Char('d') => self.scroll_by((self.rows / 2) as isize),
+            Key::Char('u') => self.scroll_by(-((self.rows / 2) as isize)),
+            Key::Char('g') | Key::Home => self.top = 0,
+            Key::Char('G') | Key::End => {
+                self.index_to(usize::MAX);
+                self.top = self.max_top();
+            },
+            Key::Char('n') => self.next_match(),
+            Key::Char('N') => self.previous_match(),
+            Key::Char('/') => self.prompt = Some(String::new()),
+            _ => {},
+        }
+        Flow::Continue
+    }
+
+    /// The task of this method is to index (or match against a new query) the next window of lines,
+    /// so the counts of the status bar get closer to their final value.
+    /// It does nothing once the pager `is_done`.
+    pub fn step(&mut self) {
+        if self.failed.is_some() {
+            return;
+        }
+        if self.checked == self.offsets.len() {
+            self.index_window();
+            return;
+        }
+        let to = (self.checked + WINDOW).min(self.offsets.len());
+        match self.read_lines(self.checked, to) {
+            Ok(lines) => {
+                for (i, line) in (self.checked..).zip(lines) {
+                    if self.matcher.is_match(&line) {
+                        self.matches.push(i);
+                    }
+                }
+                self.checked = to;
+            },
+            Err(e) => self.fail(e),
+        }
+    }
+
+    /// This method returns the text of the status bar, without any style:
+    /// the typed query after `/`, otherwise the path, the matches, the lines on the screen and the keys.
+    /// The counts that may still grow, because the file was not read up to the end, are followed by a `+`.
+    pub fn status(&self) -> String {
+        if let Some(prompt) = &self.prompt {
+            return format!("/{}", prompt);
+        }
+        let more = |done: bool| if done { "" } else { "+" };
+        let matches = match (self.current, self.matches.len(), self.is_done()) {
+            (_, 0, true) => String::from("no matches"),
+            (Some(c), total, done) => format!("match {}/{}{}", c + 1, total, more(done)),
+            (None, 1, true) => String::from("1 match"),
+            (None, total, done) => format!("{}{} matches", total, more(done)),
+        };
+        let last = (self.top + self.rows).min(self.offsets.len());
+        format!(
+            "{}  {}  lines {}-{}/{}{}  \"{}\"  {}",
+            self.path,
+            matches,
+            (self.top + 1).min(last),
+            last,
+            self.offsets.len(),
+            more(self.ended),
+            self.matcher.query(),
+            HINTS,
+        )
+    }
+
+    /// The task of this method is to render the screen: the lines in view, then the status bar.
+    /// Each line is preceded by a marker column, which holds a `>` on the line of the current match.
+    /// Only the lines in view are read from the file.
+    ///
+    /// # Arguments
+    ///
+    /// * `columns` - The width of the screen.
+    ///
+    /// # Returns
+    ///
+    /// The frame as a `String`, which starts by moving the cursor home and clears what it overwrites.
+    pub fn render(&mut self, columns: usize) -> String {
+        self.index_to(self.top + self.rows);
+        let shown = (self.top + self.rows).min(self.offsets.len());
+        let lines = match self.read_lines(self.top, shown) {
+            Ok(lines) => lines,
+            Err(e) => {
+                self.fail(e);
+                Vec::new()
+            },
+        };
+        let mut frame = String::from("\x1b[H");
+        let marked = self.current.map(|c| self.matches[c]);
+        let mut lines = (self.top..).zip(lines);
+        for _ in 0..self.rows {
+            match lines.next() {
+                Some((i, line)) => {
+                    let cell = Cell::new(&self.matcher, &self.look, line, self.skipped + i + 1, self.offsets[i], 0);
+                    let fit = Fit::Truncate(columns.saturating_sub(MARKER_WIDTH + cell.indent(self.gutter)).max(1));
+                    frame.push(if marked == Some(i) { '>' } else { ' ' });
+                    frame.push_str(&cell.to_text(Some(true), self.gutter, Some(&self.path), fit));
+                },
+                None => frame.push('~'),
+            }
+            frame.push_str("\x1b[K\r\n");
+        }
+        let status = clip(&self.status(), columns);
+        let fill = " ".repeat(columns.saturating_sub(width::str_width(&status)));
+        match self.look.colors {
+            true => frame.push_str(&format!("\x1b[7m{}{}{}", status, fill, ANSIStyle::Reset.as_str())),
+            false => frame.push_str(&format!("{}{}", status, fill)),
+        }
+        frame
+    }
+
+    /// This method returns the largest `top` that still fills the screen with the lines indexed so far.
+    fn max_top(&self) -> usize {
+        self.offsets.len().saturating_sub(self.rows)
+    }
+
+    /// This method moves the view by a number of lines, up when it is negative.
+    fn scroll_by(&mut self, delta: isize) {
+        let top = self.top.saturating_add_signed(delta);
+        self.index_to(top + self.rows);
+        self.top = top.min(self.max_top());
+    }
+
+    /// This method jumps to the first match after the current one (or after the top of the view),
+    /// wrapping around to the first match of the file.
+    /// The lines are matched until one is found, and not further.
+    fn next_match(&mut self) {
+        let after = match self.current {
+            Some(c) => self.matches[c] + 1,
+            None => self.top,
+        };
+        loop {
+            let next = self.matches.partition_point(|&m| m < after);
+            if next < self.matches.len() {
+                return self.jump(next);
+            }
+            if self.is_done() {
+                break;
+            }
+            self.step();
+        }
+        self.jump(0);
+    }
+
+    /// This method jumps to the last match before the current one (or before the top of the view),
+    /// wrapping around to the last match of the file, which needs the whole file to be matched.
+    fn previous_match(&mut self) {
+        let before = match self.current {
+            Some(c) => self.matches[c],
+            None => self.top,
+        };
+        while self.checked < before && !self.is_done() {
+            self.step();
+        }
+        let previous = self.matches.partition_point(|&m| m < before);
+        if previous > 0 {
+            return self.jump(previous - 1);
+        }
+        while !self.is_done() {
+            self.step();
+        }
+        self.jump(self.matches.len().wrapping_sub(1));
+    }
+
+    /// This method makes a match the current one, and scrolls it into view if it is not on the screen.
+    fn jump(&mut self, index: usize) {
+        let Some(&line) = self.matches.get(index) else {
+            return;
+        };
+        self.current = Some(index);
+        if line < self.top || line >= self.top + self.rows {
+            self.index_to(line + self.rows);
+            self.top = line.saturating_sub(self.rows / 3).min(self.max_top());
+        }
+    }
+
+    /// This method replaces the query, and jumps to its first match from the top of the view.
+    /// The lines that were indexed are matched again as the new query needs them.
+    fn set_query(&mut self, query: &str) {
+        self.matcher = Matcher::with_word(query, self.case.ignore_case(query), self.matcher.word());
+        self.matches.clear();
+        self.checked = 0;
+        self.current = None;
+        self.next_match();
+    }
+
+    /// This method indexes windows of lines until there are `count` of them, or the lines end.
+    fn index_to(&mut self, count: usize) {
+        while self.offsets.len() < count && !self.ended {
+            self.index_window();
+        }
+    }
+
+    /// The task of this method is to index the next `WINDOW` lines, from where the last indexed line ends.
+    /// Each line is also matched against the query, as long as the lines before it all were.
+    fn index_window(&mut self) {
+        if self.ended {
+            return;
+        }
+        if let Err(e) = self.reader.seek(SeekFrom::Start(self.end)) {
+            return self.fail(e);
+        }
+        let mut buf = Vec::new();
+        for _ in 0..WINDOW {
+            if self.skipped + self.offsets.len() >= self.limit.1 {
+                self.ended = true;
+                return;
+            }
+            buf.clear();
+            let left = self.limit.0.saturating_sub(self.end);
+            let read = match (&mut self.reader).take(left).read_until(b'\n', &mut buf) {
+                Ok(0) => {
+                    self.ended = true;
+                    return;
+                },
+                Ok(read) => read,
+                Err(e) => return self.fail(e),
+            };
+            let line = decode(&buf);
+            if let Some((matcher, max)) = &self.cutoff && matcher.is_match(&line) {
+                if self.found >= *max {
+                    self.ended = true;
+                    return;
+                }
+                self.found += 1;
+            }
+            let index = self.offsets.len();
+            self.offsets.push(self.end);
+            self.end += read as u64;
+            if self.checked == index {
+                if self.matcher.is_match(&line) {
+                    self.matches.push(index);
+                }
+                self.checked += 1;
+            }
+        }
+    }
+
+    /// The task of this method is to read indexed lines from the file.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The index of the first line.
+    /// * `to` - The index after the last line, which is at most the number of indexed lines.
+    ///
+    /// # Returns
+    ///
+    /// The lines, without their terminators.
+    ///
+    /// # Errors
+    ///
+    /// If there is a problem seeking or reading the file.
+    fn read_lines(&mut self, from: usize, to: usize) -> Result<Vec<String>, Error> {
+        if from >= to {
+            return Ok(Vec::new());
+        }
+        let start = self.offsets[from];
+        let end = self.offsets.get(to).copied().unwrap_or(self.end);
+        let mut buf = vec![0; (end - start) as usize];
+        self.reader.seek(SeekFrom::Start(start))?;
+        self.reader.read_exact(&mut buf)?;
+        Ok((from..to).map(|i| {
+            let line_end = self.offsets.get(i + 1).copied().unwrap_or(self.end);
+            decode(&buf[(self.offsets[i] - start) as usize..(line_end - start) as usize])
+        }).collect())
+    }
+
+    /// This method keeps the first error of the file, and stops reading it.
+    fn fail(&mut self, e: Error) {
+        self.failed.get_or_insert(e);
+        self.ended = true;
+    }
+}
+
+
+/// This function turns the bytes of a line into text, like the `Searcher` does:
+/// the terminator is removed, and the bytes that are not valid UTF-8 are replaced.
+fn decode(bytes: &[u8]) -> String {
+    let line = bytes.strip_suffix(b"\n").unwrap_or(bytes);
+    let line = line.strip_suffix(b"\r").unwrap_or(line);
+    String::from_utf8_lossy(line).into_owned()
+}
+
+
+/// This function cuts a text down to a number of display columns, measured like `width::str_width` does.
+fn clip(text: &str, columns: usize) -> String {
+    let mut cursor = Cursor::new(TAB_WIDTH);
+    text.chars().take_while(|&c| {
+        cursor.advance(c);
+        cursor.column() <= columns
+    }).collect()
+}
+
+
+/// This function checks whether page view should be interactive:
+/// the output is text in a terminal, the keys can be read from a terminal,
+/// and the files are not followed or watched.
+pub fn is_interactive(opts: &Options) -> bool {
+    cfg!(target_os = "linux")
+        && opts.mode == Mode::Page
+        && opts.format == Format::Text
+        && opts.html.is_none()
+        && !opts.follow
+        && !opts.watch
+        && io::stdin().is_terminal()
+        && io::stdout().is_terminal()
+}
+
+
+/// The task of this function is to page through the files in turn, on the alternate screen of the terminal.
+/// The files that could not be read are sent to the printer once the pager is closed.
+///
+/// # Arguments
+///
+/// * `files` - The paths of the files to be paged through.
+/// * `opts` - The options entered by the user.
+/// * `searcher` - The compiled search.
+/// * `look` - How the files are styled.
+/// * `printer` - Where the errors are sent.
+///
+/// # Returns
+///
+/// The `Outcome` of the files that were opened.
+///
+/// # Errors
+///
+/// If there is a problem with the terminal, or the printer fails to write.
+pub fn page(files: &[String], opts: &Options, searcher: &Searcher, look: &Look, printer: &mut dyn Printer) -> Result<Outcome, Error> {
+    let mut errors: Vec<LenzError> = Vec::new();
+    let mut outcome = Outcome::default();
+    let gutter = Gutter { column: opts.column, byte_offset: opts.byte_offset };
+    let result = terminal::Screen::enter().and_then(|_screen| {
+        for file in files.iter().filter(|f| !glob::is_excluded(&opts.exclude, f)) {
+            let rf = match crate::create_rf(file, opts, searcher, look) {
+                Ok(rf) => rf,
+                Err(e) => {
+                    errors.push(e);
+                    continue;
+                },
+            };
+            let opened = read_file(file).and_then(|mut reader| {
+                let start = match searcher.selection() {
+                    Some(selection) => selection.seek(&mut reader)?,
+                    None => (0, 0),
+                };
+                Ok((reader, start))
+            });
+            let (reader, start) = match opened {
+                Ok(opened) => opened,
+                Err(e) => {
+                    errors.push(LenzError::read(file, e));
+                    continue;
+                },
+            };
+            let mut pager = Pager::new(file, reader, start, searcher, opts.case, gutter, look.clone());
+            let flow = show(&mut pager)?;
+            // The pager only reads the part of the file that was viewed, so whether the file matches is checked apart.
+            match pager.take_error().map_or_else(|| rf.has_match(), Err) {
+                Ok(found) => outcome.matched |= found,
+                Err(e) => errors.push(LenzError::read(file, e)),
+            }
+            if flow == Flow::Quit {
+                break;
+            }
+        }
+        Ok(())
+    });
+    for e in &errors {
+        printer.error(e)?;
+    }
+    printer.finish(None)?;
+    result.map(|_| Outcome { failed: !errors.is_empty(), ..outcome })
+}
+
+
+/// The task of this function is to draw a `Pager` and give it the keys pressed, until it closes the file.
+///
+/// # Errors
+///
+/// If there is a problem reading the keys or writing to the terminal.
+fn show<R: BufRead + Seek>(pager: &mut Pager<R>) -> Result<Flow, Error> {
+    let mut out = io::stdout().lock();
+    loop {
+        pager.resize(width::terminal_height().unwrap_or(24));
+        write!(out, "{}", pager.render(width::terminal_width().unwrap_or(80)))?;
+        out.flush()?;
+        // Until a key is pressed, the rest of the file is indexed a window at a time, so the counts settle.
+        if !pager.is_done() && !terminal::is_pending() {
+            pager.step();
+            continue;
+        }
+        match pager.handle(terminal::read_key()?) {
+            Flow::Continue => {},
+            flow => return Ok(flow),
+        }
+    }
+}
+
+
+#[cfg(target_os = "linux")]
+mod terminal {
+    use std::io::{ self, Error, Write, };
+    use super::{ Key, };
+
+    /// The terminal in raw mode on its alternate screen, which is put back the way it was when dropped.
+    pub struct Screen {
+        saved: libc::termios,
+    }
+
+    impl Screen {
+        /// The task of this function is to put the terminal in raw mode (keys are read one by one, without echo)
+        /// and switch to its alternate screen, hiding the cursor and turning off line wrapping.
+        ///
+        /// # Errors
+        ///
+        /// If the settings of the terminal cannot be read or changed.
+        pub fn enter() -> Result<Self, Error> {
+            // SAFETY: `termios` is plain data, which `tcgetattr` fills in when it succeeds.
+            let mut saved: libc::termios = unsafe { std::mem::zeroed() };
+            if unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut saved) } != 0 {
+                return Err(Error::last_os_error());
+            }
+            let mut raw = saved;
+            raw.c_lflag &= !(libc::ICANON | libc::ECHO | libc::ISIG | libc::IEXTEN);
+            raw.c_iflag &= !(libc::IXON | libc::ICRNL);
+            raw.c_cc[libc::VMIN] = 1;
+            raw.c_cc[libc::VTIME] = 0;
+            // SAFETY: `raw` is a valid `termios`, copied from the one of the terminal.
+            if unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSAFLUSH, &raw) } != 0 {
+                return Err(Error::last_os_error());
+            }
+            let mut out = io::stdout();
+            write!(out, "\x1b[?1049h\x1b[?25l\x1b[?7l")?;
+            out.flush()?;
+            Ok(Self { saved })
+        }
+    }
+
+    impl Drop for Screen {
+        fn drop(&mut self) {
+            let mut out = io::stdout();
+            let _ = write!(out, "\x1b[?7h\x1b[?25h\x1b[?1049l");
+            let _ = out.flush();
+            // SAFETY: `saved` is the `termios` the terminal had before.
+            unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSAFLUSH, &self.saved) };
+        }
+    }
+
+    /// The task of this function is to wait for a key press and read all of its bytes.
+    /// An escape byte that is not quickly followed by more bytes is the `Esc` key itself.
+    ///
+    /// # Errors
+    ///
+    /// If there is a problem reading the terminal.
+    pub fn read_key() -> Result<Key, Error> {
+        let mut bytes = [0u8; 8];
+        let mut len = read(&mut bytes[..1])?;
+        let more = match bytes[0] {
+            0x1b if ready(30) => bytes.len() - 1,
+            b if b >= 0xc0 => (b.leading_ones() as usize - 1).min(3),
+            _ => 0,
+        };
+        if more > 0 {
+            len += read(&mut bytes[1..1 + more])?;
+        }
+        Ok(Key::parse(&bytes[..len]))
+    }
+
+    /// Reads at least one byte from the terminal.
+    fn read(buf: &mut [u8]) -> Result<usize, Error> {
+        loop {
+            // SAFETY: The buffer is valid for its whole length.
+            let n = unsafe { libc::read(libc::STDIN_FILENO, buf.as_mut_ptr().cast(), buf.len()) };
+            match n {
+                n if n > 0 => return Ok(n as usize),
+                0 => return Err(Error::from(io::ErrorKind::UnexpectedEof)),
+                _ if Error::last_os_error().kind() == io::ErrorKind::Interrupted => continue,
+                _ => return Err(Error::last_os_error()),
+            }
+        }
+    }
+
+    /// This function checks whether a key press is waiting to be read, without waiting for one.
+    pub fn is_pending() -> bool {
+        ready(0)
+    }
+
+    /// Checks whether bytes arrive from the terminal within `timeout` milliseconds.
+    fn ready(timeout: libc::c_int) -> bool {
+        let mut fd = libc::pollfd { fd: libc::STDIN_FILENO, events: libc::POLLIN, revents: 0 };
+        // SAFETY: `fd` is a single valid `pollfd`.
+        unsafe { libc::poll(&mut fd, 1, timeout) > 0 }
+    }
+}
+
+
+#[cfg(not(target_os = "linux"))]
+mod terminal {
+    use std::io::{ Error, ErrorKind, };
+    use super::{ Key, };
+
+    /// The terminal in raw mode, which is not supported on this platform.
+    pub struct Screen;
+
+    impl Screen {
+        pub fn enter() -> Result<Self, Error> {
+            Err(Error::from(ErrorKind::Unsupported))
+        }
+    }
+
+    pub fn read_key() -> Result<Key, Error> {
+        Err(Error::from(ErrorKind::Unsupported))
+    }
+
+    pub fn is_pending() -> bool {
+        false
+    }
+}
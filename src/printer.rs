@@ -0,0 +1,479 @@
+//! This module is related to **displaying** the results of a search.
+//! A search does not print anything by itself: it sends structured events
+//! (file start, matched line, context line, file end, error, notice) to a `Printer`,
+//! which turns them into one of the output formats and writes them to any `io::Write`.
+//! This way, the output can be captured by tests or by programs using `lenz` as a library.
+//!
+//! The printers of the terminal live here; the printers of the report formats
+//! live next to the formats themselves (`csv`, `sarif`, `markdown` and `html`).
+
+
+use std::io::{ Error, Write, };
+use std::mem;
+use crate::{ json, csv, sarif, markdown, html, hyperlink, width, };
+use crate::cells::{ Cell, Fit, Gutter, };
+use crate::errors::{ LenzError, };
+use crate::opts::{ Options, Format, Mode, };
+use crate::rf::{ Shown, };
+use crate::style::{ ANSIStyle, Look, };
+use crate::theme::{ Slot, };
+
+
+/// A `Printer` receives the events of a search and displays them.
+/// Every method has a default that ignores the event, except for the matched lines and the errors.
+pub trait Printer {
+    /// Whether `file_start` should be given the number of matches of each file.
+    /// Counting needs an extra pass over the file, so it is only done when asked for.
+    fn needs_count(&self) -> bool {
+        false
+    }
+
+    /// The search is about to start.
+    fn begin(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// A file is about to be searched. `count` is its number of matches, if it was counted.
+    fn file_start(&mut self, _path: &str, _count: Option<usize>) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// A line of the file matched the query.
+    fn matched(&mut self, path: &str, cell: &Cell) -> Result<(), Error>;
+
+    /// A line of the file did not match the query, but is shown around the matches (page view).
+    fn context(&mut self, _path: &str, _cell: &Cell) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// The search of a file is over. `shown` tells how many matched lines were sent.
+    fn file_end(&mut self, _path: &str, _shown: Shown) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// With `--diff`, a matched line was added since the previous run of `--watch`,
+    /// or removed if `added` is unset. It is left out by default.
+    fn changed(&mut self, _path: &str, _cell: &Cell, _added: bool) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// A file could not be searched.
+    fn error(&mut self, error: &LenzError) -> Result<(), Error>;
+
+    /// A message about the search itself, already styled, like a followed file that was replaced.
+    /// It is left out by default, so that it does not end up among results read by other programs.
+    fn notice(&mut self, _text: &str) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// The search is over. `truncated` holds the limits in words, if some matched lines were left out.
+    /// While following files, it is called again after each error, which only reports the new ones.
+    fn finish(&mut self, _truncated: Option<&str>) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+
+/// The task of this function is to choose the `Printer` asked for by the options.
+///
+/// # Arguments
+///
+/// * `opts` - The options entered by the user.
+/// * `look` - How the output looks, for the printers that style it.
+/// * `out` - Where the results are written (the standard output, for `run`).
+/// * `err` - Where the errors and notices of the formats read by other programs are written.
+///
+/// # Returns
+///
+/// The `Printer`, ready to receive the events of a search.
+pub fn for_options<'a>(opts: &Options, look: &Look, out: &'a mut dyn Write, err: &'a mut dyn Write) -> Box<dyn Printer + 'a> {
+    let gutter = Gutter { column: opts.column, byte_offset: opts.byte_offset };
+    let page = opts.mode == Mode::Page;
+    if let Some(report) = &opts.html {
+        return Box::new(html::Html::new(out, look.clone(), report, &opts.query, gutter, page));
+    }
+    if opts.mode.lists() {
+        let expected = opts.mode == Mode::FilesWithMatches;
+        return Box::new(List::new(out, err, look.clone(), expected, opts.null));
+    }
+    match opts.format {
+        Format::Json => Box::new(Json::new(out, opts.only_matching, opts.max_count, opts.max_total)),
+        Format::Vimgrep => Box::new(Plain::new(out, err)),
+        Format::Sarif => Box::new(sarif::Sarif::new(out, &opts.query)),
+        Format::Markdown => Box::new(markdown::Markdown::new(out, gutter)),
+        Format::Csv | Format::Tsv => {
+            Box::new(csv::Csv::new(out, err, &opts.columns, &opts.query, opts.format == Format::Tsv))
+        },
+        Format::Text if opts.mode == Mode::Count => Box::new(Count::new(out, look.clone())),
+        Format::Text => Box::new(Standard::new(out, look.clone(), gutter, page, opts.only_matching, get_fit(opts))),
+    }
+}
+
+
+/// This function returns how long lines are fitted into the terminal, as asked for by the options.
+/// Without a terminal, `--wrap` wraps to 80 columns.
+pub fn get_fit(opts: &Options) -> Fit {
+    match opts.max_columns {
+        _ if opts.wrap => Fit::Wrap(width::terminal_width().unwrap_or(80)),
+        Some(max) => Fit::Truncate(max),
+        None => Fit::Full,
+    }
+}
+
+
+/// The usual colored display of the cells, in cell view or page view.
+pub struct Standard<W: Write> {
+    out: W,
+    look: Look,
+    gutter: Gutter,
+    page: bool,
+    only_matching: bool,
+    fit: Fit,
+    errors: Vec<String>,
+}
+
+
+impl<W: Write> Standard<W> {
+    /// `Standard` Constructor.
+    /// The `look` styles the heads, the footer and the errors report; the cells bring their own.
+    pub fn new(out: W, look: Look, gutter: Gutter, page: bool, only_matching: bool, fit: Fit) -> Self {
+        Self { out, look, gutter, page, only_matching, fit, errors: Vec::new() }
+    }
+}
+
+
+impl<W: Write> Printer for Standard<W> {
+    fn needs_count(&self) -> bool {
+        true
+    }
+
+    fn file_start(&mut self, path: &str, count: Option<usize>) -> Result<(), Error> {
+        writeln!(self.out, "\n{}\n", get_head(&self.look, path, count))
+    }
+
+    fn matched(&mut self, path: &str, cell: &Cell) -> Result<(), Error> {
+        if self.only_matching {
+            for row in cell.to_text_only_matching(self.gutter, Some(path)) {
+                writeln!(self.out, "{}", row)?;
+            }
+            return Ok(());
+        }
+        writeln!(self.out, "{}", cell.to_text(if self.page { Some(true) } else { None }, self.gutter, Some(path), self.fit))
+    }
+
+    fn context(&mut self, path: &str, cell: &Cell) -> Result<(), Error> {
+        writeln!(self.out, "{}", cell.to_text(Some(true), self.gutter, Some(path), self.fit))
+    }
+
+    fn changed(&mut self, path: &str, cell: &Cell, added: bool) -> Result<(), Error> {
+        // Byte offsets are not remembered between runs, so they are not displayed.
+        let gutter = Gutter { byte_offset: false, ..self.gutter };
+        let sign = if added { self.look.paint(Slot::Added, "+") } else { self.look.paint(Slot::Removed, "-") };
+        writeln!(self.out, "{}{}", sign, cell.to_text(None, gutter, Some(path), Fit::Full))
+    }
+
+    fn error(&mut self, error: &LenzError) -> Result<(), Error> {
+        self.errors.push(error.colored(&self.look));
+        Ok(())
+    }
+
+    fn notice(&mut self, text: &str) -> Result<(), Error> {
+        writeln!(self.out, "{}", text)
+    }
+
+    fn finish(&mut self, truncated: Option<&str>) -> Result<(), Error> {
+        if let Some(limits) = truncated {
+            writeln!(self.out, "\n{}", get_truncated_footer(&self.look, limits))?;
+        }
+        let errors = mem::take(&mut self.errors);
+        if !errors.is_empty() {
+            write!(self.out, "{}", get_errors_report(&self.look, &errors))?;
+        }
+        Ok(())
+    }
+}
+
+
+/// Only the head of each file, with its number of matches.
+pub struct Count<W: Write> {
+    out: W,
+    look: Look,
+    errors: Vec<String>,
+}
+
+
+impl<W: Write> Count<W> {
+    /// `Count` Constructor.
+    pub fn new(out: W, look: Look) -> Self {
+        Self { out, look, errors: Vec::new() }
+    }
+}
+
+
+impl<W: Write> Printer for Count<W> {
+    fn needs_count(&self) -> bool {
+        true
+    }
+
+    fn file_start(&mut self, path: &str, count: Option<usize>) -> Result<(), Error> {
+        writeln!(self.out, "{}", get_head(&self.look, path, count))
+    }
+
+    fn matched(&mut self, _path: &str, _cell: &Cell) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn error(&mut self, error: &LenzError) -> Result<(), Error> {
+        self.errors.push(error.colored(&self.look));
+        Ok(())
+    }
+
+    fn notice(&mut self, text: &str) -> Result<(), Error> {
+        writeln!(self.out, "{}", text)
+    }
+
+    fn finish(&mut self, truncated: Option<&str>) -> Result<(), Error> {
+        if let Some(limits) = truncated {
+            writeln!(self.out, "\n{}", get_truncated_footer(&self.look, limits))?;
+        }
+        let errors = mem::take(&mut self.errors);
+        if !errors.is_empty() {
+            write!(self.out, "{}", get_errors_report(&self.look, &errors))?;
+        }
+        Ok(())
+    }
+}
+
+
+/// Uncolored `path:line:col:text` rows, one per match, for editors (`--vimgrep`).
+/// Everything else goes to `err`, so it is not taken for a match.
+pub struct Plain<W: Write, E: Write> {
+    out: W,
+    err: E,
+}
+
+
+impl<W: Write, E: Write> Plain<W, E> {
+    /// `Plain` Constructor.
+    pub fn new(out: W, err: E) -> Self {
+        Self { out, err }
+    }
+}
+
+
+impl<W: Write, E: Write> Printer for Plain<W, E> {
+    fn matched(&mut self, path: &str, cell: &Cell) -> Result<(), Error> {
+        for row in get_vimgrep_rows(path, cell) {
+            writeln!(self.out, "{}", row)?;
+        }
+        Ok(())
+    }
+
+    fn error(&mut self, error: &LenzError) -> Result<(), Error> {
+        writeln!(self.err, "{}", error.plain())
+    }
+
+    fn notice(&mut self, text: &str) -> Result<(), Error> {
+        writeln!(self.err, "{}", text)
+    }
+
+    fn finish(&mut self, truncated: Option<&str>) -> Result<(), Error> {
+        if let Some(limits) = truncated {
+            writeln!(self.err, "{}", get_truncated_footer(&Look::plain(), limits))?;
+        }
+        Ok(())
+    }
+}
+
+
+/// One JSON object per line (`--format json`).
+pub struct Json<W: Write> {
+    out: W,
+    only_matching: bool,
+    max_count: Option<usize>,
+    max_total: Option<usize>,
+    errors: Vec<String>,
+}
+
+
+impl<W: Write> Json<W> {
+    /// `Json` Constructor.
+    /// The limits are only used to describe truncated results.
+    pub fn new(out: W, only_matching: bool, max_count: Option<usize>, max_total: Option<usize>) -> Self {
+        Self { out, only_matching, max_count, max_total, errors: Vec::new() }
+    }
+}
+
+
+impl<W: Write> Printer for Json<W> {
+    fn file_start(&mut self, path: &str, count: Option<usize>) -> Result<(), Error> {
+        // The files are only counted when counting is the display mode.
+        if let Some(count) = count {
+            writeln!(self.out, "{}", json::count(path, count))?;
+        }
+        Ok(())
+    }
+
+    fn matched(&mut self, path: &str, cell: &Cell) -> Result<(), Error> {
+        if self.only_matching {
+            for obj in json::matches(path, cell) {
+                writeln!(self.out, "{}", obj)?;
+            }
+            return Ok(());
+        }
+        writeln!(self.out, "{}", json::line(path, cell))
+    }
+
+    fn context(&mut self, path: &str, cell: &Cell) -> Result<(), Error> {
+        if self.only_matching {
+            return Ok(());
+        }
+        writeln!(self.out, "{}", json::line(path, cell))
+    }
+
+    fn error(&mut self, error: &LenzError) -> Result<(), Error> {
+        self.errors.push(error.to_json());
+        Ok(())
+    }
+
+    fn finish(&mut self, truncated: Option<&str>) -> Result<(), Error> {
+        if truncated.is_some() {
+            writeln!(self.out, "{}", json::truncated(self.max_count, self.max_total))?;
+        }
+        for e in mem::take(&mut self.errors) {
+            writeln!(self.out, "{}", e)?;
+        }
+        Ok(())
+    }
+}
+
+
+/// The bare paths of the files with matches (or without), for other programs (`-l` and `-L`).
+/// The errors go to `err`, so they do not end up among the paths.
+pub struct List<W: Write, E: Write> {
+    out: W,
+    err: E,
+    look: Look,
+    expected: bool,
+    null: bool,
+}
+
+
+impl<W: Write, E: Write> List<W, E> {
+    /// `List` Constructor.
+    /// With `expected` set, the files with matches are listed, otherwise the files without.
+    /// The `look` only styles the errors.
+    pub fn new(out: W, err: E, look: Look, expected: bool, null: bool) -> Self {
+        Self { out, err, look, expected, null }
+    }
+}
+
+
+impl<W: Write, E: Write> Printer for List<W, E> {
+    fn matched(&mut self, _path: &str, _cell: &Cell) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn file_end(&mut self, path: &str, shown: Shown) -> Result<(), Error> {
+        if (shown.lines > 0) == self.expected {
+            write!(self.out, "{}{}", path, if self.null { '\0' } else { '\n' })?;
+        }
+        Ok(())
+    }
+
+    fn error(&mut self, error: &LenzError) -> Result<(), Error> {
+        writeln!(self.err, "{}", error.colored(&self.look))
+    }
+
+    fn notice(&mut self, text: &str) -> Result<(), Error> {
+        writeln!(self.err, "{}", text)
+    }
+}
+
+
+/// The task of this function is to describe each match of a `Cell` in the `path:line:col:text`
+/// form understood by editors (vim's quickfix, Emacs' grep-mode, ...).
+/// The column is counted in bytes from `1`, as editors expect.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file the `Cell` belongs to.
+/// * `cell` - The line.
+///
+/// # Returns
+///
+/// A `Vec<String>` containing one row per match, each with the whole line.
+pub fn get_vimgrep_rows(path: &str, cell: &Cell) -> Vec<String> {
+    cell.spans().into_iter().map(|(x, _)| {
+        format!("{}:{}:{}:{}", path, cell.lineno(), x + 1, cell.line())
+    }).collect()
+}
+
+
+/// This function returns a constant `head` consisting of the
+/// `file name` and the `number` of matching occurrences, if it is known.
+/// The file name is a hyperlink to the file when the `look` has hyperlinks.
+///
+/// # Returns
+///
+/// The output is a new `String`.
+pub fn get_head(look: &Look, path: &str, count: Option<usize>) -> String {
+    let count = match count {
+        Some(c) => format!(" {}", look.paint(Slot::Count, &format!("[{}]", c))),
+        None => String::new(),
+    };
+    format!(
+        "{}- Filename: {}{}{}",
+        look.ansi(ANSIStyle::Bold),
+        hyperlink::file(&look.hyperlink_format, &look.paths, path, &look.paint(Slot::Path, &format!("'{}'", path))),
+        look.ansi(ANSIStyle::Reset),
+        count,
+    )
+}
+
+
+/// This function returns a `footer` noting that some matched lines were not displayed
+/// because of `--max-count` or `--max-total`.
+///
+/// # Arguments
+///
+/// * `look` - How the footer is styled.
+/// * `limits` - The limits, in words.
+pub fn get_truncated_footer(look: &Look, limits: &str) -> String {
+    format!(
+        "{}{}* Results were truncated: {}{}{}.{}",
+        look.ansi(ANSIStyle::Bold),
+        look.ansi(ANSIStyle::FGYellow),
+        look.ansi(ANSIStyle::Reset),
+        look.ansi(ANSIStyle::Italic),
+        limits,
+        look.ansi(ANSIStyle::Reset),
+    )
+}
+
+
+/// This function is responsible for reporting files that had problems
+/// and could not be opened and were thrown into the trash.
+/// At the end of each output, a report of the corrupted files will be given.
+///
+/// # Arguments
+///
+/// * `look` - How the report is styled.
+/// * `errors` - The messages of the errors that should be displayed.
+///
+/// # Returns
+///
+/// The report as a `String`, ending with a line break.
+pub fn get_errors_report(look: &Look, errors: &[String]) -> String {
+    let mut report = format!(
+        "\n{}{}{} \n",
+        look.ansi(ANSIStyle::Bold),
+        look.paint(Slot::Error, "* Errors Report:"),
+        look.ansi(ANSIStyle::Reset),
+    );
+    for (i, e_msg) in errors.iter().enumerate() {
+        let br = if i == errors.len() - 1 { "└──" } else { "├──" };
+        report.push_str(&format!("{} {}\n", look.paint(Slot::Error, &format!("{}[{}]", br, i + 1)), e_msg));
+    }
+    report
+}
@@ -0,0 +1,189 @@
+//! This module is related to searching only a **part** of a file.
+//! A `Selection` is either a range of lines or a range of bytes.
+//! Either way, the line numbers that are displayed stay the true line numbers of the file.
+
+
+use std::fs::{ File, };
+use std::io::{ BufRead, BufReader, Error, Seek, SeekFrom, };
+use memchr::{ memchr_iter, };
+use crate::mmap;
+
+
+/// A part of a file to be searched.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Selection {
+    /// Lines `first` to `last`, counted from `1` and both included.
+    Lines { first: usize, last: usize },
+    /// `len` bytes starting at `offset`.
+    /// Lines cut by the edges of the range are only searched partially.
+    Bytes { offset: u64, len: u64 },
+}
+
+
+impl Selection {
+    /// The task of this method is to parse a line range like `1200:1800`.
+    /// Either end can be omitted (`1200:` or `:1800`), and a single number selects one line.
+    ///
+    /// # Returns
+    ///
+    /// `Some(Selection)` if the range is valid, otherwise `None`.
+    pub fn parse_lines(value: &str) -> Option<Self> {
+        let (first, last) = match value.split_once(':') {
+            Some((f, l)) => (
+                if f.is_empty() { 1 } else { f.parse().ok()? },
+                if l.is_empty() { usize::MAX } else { l.parse().ok()? },
+            ),
+            None => {
+                let n = value.parse().ok()?;
+                (n, n)
+            },
+        };
+        if first == 0 || first > last {
+            return None;
+        }
+        Some(Selection::Lines { first, last })
+    }
+
+    /// The task of this method is to parse a byte range like `OFFSET:LEN`.
+    /// The length can be omitted (`OFFSET:`) to search up to the end of the file.
+    ///
+    /// # Returns
+    ///
+    /// `Some(Selection)` if the range is valid, otherwise `None`.
+    pub fn parse_bytes(value: &str) -> Option<Self> {
+        let (offset, len) = value.split_once(':')?;
+        let offset = offset.parse().ok()?;
+        let len = if len.is_empty() { u64::MAX } else { len.parse().ok()? };
+        Some(Selection::Bytes { offset, len })
+    }
+
+    /// The task of this method is to move a reader to the start of the selection.
+    /// Skipped lines are only scanned for newlines, without being decoded.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - A reader at the start of the file.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<(usize, u64), Error>` with the number of lines and bytes before the selection.
+    ///
+    /// # Errors
+    ///
+    /// If there is a problem reading the file.
+    pub fn skip<R: BufRead>(&self, reader: &mut R) -> Result<(usize, u64), Error> {
+        let mut bytes: u64 = 0;
+        match *self {
+            Selection::Lines { first, .. } => {
+                let mut left = first - 1;
+                while left > 0 {
+                    let buf = reader.fill_buf()?;
+                    if buf.is_empty() {
+                        break;
+                    }
+                    let (used, found) = match memchr_iter(b'\n', buf).nth(left - 1) {
+                        Some(p) => (p + 1, left),
+                        None => (buf.len(), memchr_iter(b'\n', buf).count()),
+                    };
+                    reader.consume(used);
+                    bytes += used as u64;
+                    left -= found;
+                }
+                Ok((first - 1 - left, bytes))
+            },
+            Selection::Bytes { offset, .. } => {
+                let mut left = offset;
+                let mut lines = 0;
+                while left > 0 {
+                    let buf = reader.fill_buf()?;
+                    if buf.is_empty() {
+                        break;
+                    }
+                    let used = buf.len().min(usize::try_from(left).unwrap_or(usize::MAX));
+                    lines += memchr_iter(b'\n', &buf[..used]).count();
+                    reader.consume(used);
+                    bytes += used as u64;
+                    left -= used as u64;
+                }
+                Ok((lines, bytes))
+            },
+        }
+    }
+
+    /// The task of this method is to move a file to the start of the selection.
+    /// A range of bytes is reached with a seek, and the lines before it are counted
+    /// on a map of the file, so the skipped bytes are not copied through the buffer.
+    /// Counting the lines still scans every byte before the offset, so the cost is O(offset).
+    /// A range of lines has to be found by scanning, like `skip` does.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - A file at its start.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<(usize, u64), Error>` with the number of lines and bytes before the selection.
+    ///
+    /// # Errors
+    ///
+    /// If there is a problem mapping or seeking the file.
+    pub fn seek(&self, reader: &mut BufReader<File>) -> Result<(usize, u64), Error> {
+        let Selection::Bytes { offset, .. } = *self else {
+            return self.skip(reader);
+        };
+        let (lines, bytes) = match mmap::map_open(reader.get_ref())? {
+            Some(map) => {
+                let start = usize::try_from(offset).unwrap_or(usize::MAX).min(map.len());
+                (memchr_iter(b'\n', &map[..start]).count(), start as u64)
+            },
+            None => (0, 0),
+        };
+        reader.seek(SeekFrom::Start(bytes))?;
+        Ok((lines, bytes))
+    }
+
+    /// The number of bytes that may be read after `skip`.
+    pub fn byte_limit(&self) -> u64 {
+        match *self {
+            Selection::Lines { .. } => u64::MAX,
+            Selection::Bytes { len, .. } => len,
+        }
+    }
+
+    /// The number of the last line that may be read.
+    pub fn last_line(&self) -> usize {
+        match *self {
+            Selection::Lines { last, .. } => last,
+            Selection::Bytes { .. } => usize::MAX,
+        }
+    }
+
+    /// The task of this method is to cut the selection out of the contents of a (mapped) file.
+    ///
+    /// # Arguments
+    ///
+    /// * `hay` - The contents of the file.
+    ///
+    /// # Returns
+    ///
+    /// The selected bytes, and the number of lines before them.
+    pub fn slice<'a>(&self, hay: &'a [u8]) -> (&'a [u8], usize) {
+        match *self {
+            Selection::Lines { first, last } => {
+                let start = if first == 1 {
+                    0
+                } else {
+                    memchr_iter(b'\n', hay).nth(first - 2).map_or(hay.len(), |p| p + 1)
+                };
+                let end = memchr_iter(b'\n', &hay[start..]).nth(last - first)
+                                                           .map_or(hay.len(), |p| start + p + 1);
+                (&hay[start..end], first - 1)
+            },
+            Selection::Bytes { offset, len } => {
+                let start = usize::try_from(offset).unwrap_or(usize::MAX).min(hay.len());
+                let end = usize::try_from(offset.saturating_add(len)).unwrap_or(usize::MAX).min(hay.len());
+                (&hay[start..end], memchr_iter(b'\n', &hay[..start]).count())
+            },
+        }
+    }
+}
@@ -1,25 +1,56 @@
 //! This module is related to the structure of the files that can be searched.
-//! Any file that is `readable` can be searched and the output is displayed using matching `Cells`.
+//! Any file that is `readable` can be searched and the matching `Cells` are sent to a `Printer`.
+//! The lines are read by the `Searcher` of the library, unless the file is memory-mapped.
+//! A file that cannot be opened or read is reported to the `Printer` as an error,
+//! so it is never mistaken for a file without matches.
 
 
-use std::io::{ BufRead, };
-use crate::{ read_file, count_query, };
+use std::cell;
+use std::collections::{ VecDeque, };
+use std::io::{ Error, };
+use crate::{ read_file, mmap, };
 use crate::cells::{ Cell, };
-use crate::style::{ ANSIStyle, };
+use crate::errors::{ LenzError, };
+use crate::opts::{ Mode, Options, };
+use crate::printer::{ Printer, };
+use crate::searcher::{ Match, Searcher, };
+use crate::style::{ Look, };
+
+
+/// Lines of a file along with their line numbers.
+pub type NumberedLines = Vec<(usize, String)>;
+
+
+/// How many matched lines of a file were displayed,
+/// and whether more were left out because of a limit.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Shown {
+    pub lines: usize,
+    pub truncated: bool,
+    /// Whether the file has a match, even if none of its lines were displayed (like when counting).
+    pub matched: bool,
+    /// Whether the file could not be read to the end.
+    pub failed: bool,
+}
 
 
 /// This structure includes any `readable` file that holds search-related information.
-pub struct ReadableFile {
+pub struct ReadableFile<'a> {
     path: String,
-    query: String,
-    ignore_case: bool,
+    searcher: &'a Searcher,
+    opts: &'a Options,
+    /// How the `Cells` of the file are styled.
+    look: &'a Look,
+    mmap: bool,
+    /// Where the last read of the whole file ended: the number of complete lines and the byte offset after them.
+    reached: cell::Cell<Option<(usize, u64)>>,
 }
 
 
-impl ReadableFile {
+impl<'a> ReadableFile<'a> {
     /// `ReadableFile` Constructor.
-    pub fn new(path: String, query: String, ignore_case: bool) -> Self {
-        Self { path, query, ignore_case, }
+    pub fn new(path: String, searcher: &'a Searcher, opts: &'a Options, look: &'a Look, mmap: bool) -> Self {
+        Self { path, searcher, opts, look, mmap, reached: cell::Cell::new(None), }
     }
 
     /// `path` attr getter.
@@ -27,98 +58,371 @@ impl ReadableFile {
         &self.path
     }
 
-    /// This method displays only the lines that are matched by `Cells`.
-    pub fn print_cells(&self) {
-        println!("\n{}\n", self.get_head());
-        if let Ok(buffer) = read_file(&self.path) {
-            let mut n: usize = 1;
-            let q = &self.query;
-            for line in buffer.lines() {
-                if let Ok(line) = line {
-                    let cell = Cell::new(
-                        q.to_string(),
-                        line,
-                        n,
-                        1,
-                    );
-                    n += 1;
-                    if cell.check_line(self.ignore_case) {
-                        cell.print(None, self.ignore_case);
-                    } else {
-                        drop(cell);
-                        continue;
-                    }
-                }
-            }
+    /// This method returns where the last search of the file read up to, if it read the whole file:
+    /// the number of complete lines and the byte offset after them, which is where following the file starts.
+    pub fn reached(&self) -> Option<(usize, u64)> {
+        self.reached.get()
+    }
+
+    /// The task of this method is to search the file and send what the display mode shows
+    /// to a `Printer`: only the number of matches when counting, the matched lines,
+    /// or every line in page view. When listing files, the scan stops at the first match.
+    ///
+    /// If the file cannot be read, the error is sent instead. When counting or listing,
+    /// nothing else is sent for the file; otherwise the lines read before the failure are kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The maximum number of matched lines to send.
+    /// * `printer` - Where the events of the search are sent.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<Shown, Error>` telling how many matched lines were sent, whether there were more,
+    /// and whether the file matched or failed to be read.
+    ///
+    /// # Errors
+    ///
+    /// If the printer fails to write.
+    pub fn search(&self, limit: Option<usize>, printer: &mut dyn Printer) -> Result<Shown, Error> {
+        let counted = match self.opts.mode {
+            Mode::Count => self.get_count(limit).map(|(count, shown)| (Some(count), Some(shown))),
+            Mode::FilesWithMatches | Mode::FilesWithoutMatch => self.has_match().map(|found| (None, Some(Shown { lines: usize::from(found), ..Shown::default() }))),
+            _ if printer.needs_count() => self.get_count(limit).map(|(count, _)| (Some(count), None)),
+            // Opening the file first keeps an unreadable file from getting a head.
+            _ => read_file(&self.path).map(|_| (None, None)),
+        };
+        let (count, shown) = match counted {
+            Ok(counted) => counted,
+            Err(e) => {
+                printer.error(&LenzError::read(&self.path, e))?;
+                return Ok(Shown { failed: true, ..Shown::default() });
+            },
+        };
+        printer.file_start(&self.path, count)?;
+        let mut written = Ok(());
+        let mut sent = 0;
+        let scanned = match shown {
+            Some(shown) => Ok(shown),
+            None if self.opts.mode == Mode::Page || self.opts.context > 0 => self.scan_around(limit, |cell, matched| {
+                sent += usize::from(matched);
+                written = if matched {
+                    printer.matched(&self.path, &cell)
+                } else {
+                    printer.context(&self.path, &cell)
+                };
+                written.is_err()
+            }),
+            None => self.scan_limited(limit, |cell| {
+                sent += 1;
+                written = printer.matched(&self.path, &cell);
+                written.is_err()
+            }),
+        };
+        written?;
+        let (mut shown, failed) = match scanned {
+            Ok(shown) => (shown, None),
+            Err(e) => (Shown { lines: sent, failed: true, ..Shown::default() }, Some(e)),
+        };
+        shown.matched = shown.lines > 0 || count.is_some_and(|c| c > 0);
+        printer.file_end(&self.path, shown)?;
+        if let Some(e) = failed {
+            printer.error(&LenzError::read(&self.path, e))?;
         }
+        Ok(shown)
     }
 
-    /// This method only displays the `number` of matches in the file along with the `file path`.
-    pub fn print_count(&self) {
-        println!("{}", self.get_head());
-    }
-
-    /// This method uses `cells` to display all lines in the file alongside the `matched lines`.
-    pub fn print_page(&self) {
-        println!("\n{}\n", self.get_head());
-        if let Ok(buffer) = read_file(&self.path) {
-            let mut n: usize = 1;
-            let q = &self.query;
-            for line in buffer.lines() {
-                if let Ok(line) = line {
-                    Cell::new(
-                        q.to_string(),
-                        line,
-                        n,
-                        1,
-                    ).print(Some(true), self.ignore_case);
-                }
-                n += 1;
+    /// This method only sends the matched lines that were added or removed
+    /// compared to a previous search of the same file to the printer (see `Printer::changed`).
+    ///
+    /// # Arguments
+    ///
+    /// * `printer` - Where the lines are sent.
+    /// * `current` - The matched lines of this search, which the head counts the matches of.
+    /// * `added` - The new matched lines along with their line numbers.
+    /// * `removed` - The matched lines that are gone, along with their old line numbers.
+    ///
+    /// # Errors
+    ///
+    /// If the printer fails to write.
+    pub fn send_diff(&self, printer: &mut dyn Printer, current: &[(usize, String)], added: &[(usize, String)], removed: &[(usize, String)]) -> Result<(), Error> {
+        let matcher = self.searcher.matcher();
+        let count = current.iter().filter_map(|(_, line)| matcher.count(line)).sum();
+        printer.file_start(&self.path, Some(count))?;
+        for (is_added, lines) in [(false, removed), (true, added)] {
+            for (n, line) in lines {
+                let cell = Cell::new(matcher, self.look, line.to_string(), *n, 0, 0);
+                printer.changed(&self.path, &cell, is_added)?;
             }
         }
+        printer.file_end(&self.path, Shown { lines: current.len(), matched: !current.is_empty(), ..Shown::default() })
     }
 
-    /// This method returns a constant `head` consisting of the
-    /// `file name` and the `number` of matching occurrences.
+    /// This method returns the lines of the file that are matched, without displaying them.
     ///
     /// # Returns
     ///
-    /// The output is a new `String`.
-    fn get_head(&self) -> String {
-        format!(
-            "{}- Filename: {}{}'{}'{} {}[{}]{}",
-            ANSIStyle::Bold.as_str(),
-            ANSIStyle::Italic.as_str(),
-            ANSIStyle::FGWhite.as_str(),
-            self.path,
-            ANSIStyle::Reset.as_str(),
-            ANSIStyle::FGGreen.as_str(),
-            self.get_count(&self.query),
-            ANSIStyle::Reset.as_str(),
-        )
+    /// The matched lines along with their line numbers.
+    ///
+    /// # Errors
+    ///
+    /// If there is a problem opening or reading the file.
+    pub fn matched_lines(&self) -> Result<NumberedLines, Error> {
+        let mut lines = Vec::new();
+        if self.mmap {
+            self.scan_mapped(|n, _, line| {
+                lines.push((n, String::from_utf8_lossy(line).into_owned()));
+                false
+            })?;
+            return Ok(lines);
+        }
+        self.scan_matches(false, |found| {
+            lines.push((found.line_number, found.line));
+            false
+        })?;
+        Ok(lines)
+    }
+
+    /// This method checks whether the file has any match.
+    /// The scan stops at the first match instead of reading the whole file.
+    ///
+    /// # Errors
+    ///
+    /// If there is a problem opening or reading the file.
+    pub fn has_match(&self) -> Result<bool, Error> {
+        if self.mmap {
+            return Ok(match mmap::map_file(&self.path)? {
+                Some(map) => mmap::matched_lines(self.select(&map).0, self.searcher.matcher()).next().is_some(),
+                None => false,
+            });
+        }
+        let mut found = false;
+        self.scan_matches(false, |_| {
+            found = true;
+            true
+        })?;
+        Ok(found)
     }
 
     /// The task of this method is to `count` the matches in the file.
-    /// This is done with the help of functions in the main module.
+    /// This is done with the help of the shared `Matcher`.
     ///
     /// # Arguments
     ///
-    /// * `query` - The string to search for.
+    /// * `limit` - The maximum number of matched lines whose matches are counted.
     ///
     /// # Returns
     ///
-    /// The output is a `usize`, which is the number of `matches`.
-    fn get_count(&self, query: &str) -> usize {
+    /// The output is a `usize`, which is the number of `matches`, along with
+    /// the matched lines that were counted. Without a limit, the lines are not tallied.
+    ///
+    /// # Errors
+    ///
+    /// If there is a problem opening or reading the file.
+    fn get_count(&self, limit: Option<usize>) -> Result<(usize, Shown), Error> {
         let mut c: usize = 0;
-        if let Ok(buffer) = read_file(&self.path) {
-            for line in buffer.lines() {
-                if let Ok(line) = line {
-                    if let Some(count) = count_query(query, &line, self.ignore_case) {
-                        c += count;
-                    }
+        if limit.is_some() {
+            let shown = self.scan_limited(limit, |cell| {
+                c += cell.count_matches();
+                false
+            })?;
+            return Ok((c, shown));
+        }
+        if self.mmap {
+            return Ok(match mmap::map_file(&self.path)? {
+                Some(map) => {
+                    self.reach_mapped(&map);
+                    (mmap::count(self.select(&map).0, self.searcher.matcher()), Shown::default())
+                },
+                None => (0, Shown::default()),
+            });
+        }
+        self.scan_matches(false, |found| {
+            c += found.spans.len();
+            false
+        })?;
+        Ok((c, Shown::default()))
+    }
+
+    /// The task of this method is to walk through the matched lines of the file as `Cells`,
+    /// stopping the read as soon as the `limit` is exceeded.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The maximum number of matched lines to walk through.
+    /// * `f` - A closure receiving each matched `Cell`, which returns `true` to stop the walk.
+    ///
+    /// # Returns
+    ///
+    /// How many matched lines were walked through, and whether there were more.
+    ///
+    /// # Errors
+    ///
+    /// If there is a problem opening or reading the file.
+    fn scan_limited<F: FnMut(Cell) -> bool>(&self, limit: Option<usize>, mut f: F) -> Result<Shown, Error> {
+        let mut shown = Shown::default();
+        self.scan_cells(|cell| {
+            if limit.is_some_and(|l| shown.lines >= l) {
+                shown.truncated = true;
+                return true;
+            }
+            shown.lines += 1;
+            f(cell)
+        })?;
+        Ok(shown)
+    }
+
+    /// The task of this method is to walk through every line of the file as `Cells`,
+    /// stopping the read right before the first matched line over the `limit`.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The maximum number of matched lines to walk through.
+    /// * `f` - A closure receiving each `Cell` and whether it is matched, which returns `true` to stop the walk.
+    ///
+    /// # Returns
+    ///
+    /// How many matched lines were walked through, and whether there were more.
+    ///
+    /// # Errors
+    ///
+    /// If there is a problem opening or reading the file.
+    fn scan_page<F: FnMut(Cell<'a>, bool) -> bool>(&self, limit: Option<usize>, mut f: F) -> Result<Shown, Error> {
+        let mut shown = Shown::default();
+        self.scan_matches(true, |found| {
+            let matched = !found.spans.is_empty();
+            let cell = Cell::new(self.searcher.matcher(), self.look, found.line, found.line_number, found.offset, 1);
+            if matched {
+                if limit.is_some_and(|l| shown.lines >= l) {
+                    shown.truncated = true;
+                    return true;
                 }
+                shown.lines += 1;
+            }
+            f(cell, matched)
+        })?;
+        Ok(shown)
+    }
+
+    /// The task of this method is to walk through the lines that are shown around the matches:
+    /// every line in page view, otherwise the matched lines along with `context` lines before and after them.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The maximum number of matched lines to walk through.
+    /// * `f` - A closure receiving each `Cell` and whether it is matched, which returns `true` to stop the walk.
+    ///
+    /// # Errors
+    ///
+    /// If there is a problem opening or reading the file.
+    fn scan_around<F: FnMut(Cell<'a>, bool) -> bool>(&self, limit: Option<usize>, mut f: F) -> Result<Shown, Error> {
+        let context = self.opts.context;
+        if self.opts.mode == Mode::Page {
+            return self.scan_page(limit, f);
+        }
+        let mut before: VecDeque<Cell> = VecDeque::with_capacity(context + 1);
+        let mut after = 0;
+        self.scan_page(limit, |cell, matched| {
+            if matched {
+                after = context;
+                return before.drain(..).any(|c| f(c, false)) || f(cell, true);
+            }
+            if after > 0 {
+                after -= 1;
+                return f(cell, false);
             }
+            before.push_back(cell);
+            if before.len() > context {
+                before.pop_front();
+            }
+            false
+        })
+    }
+
+    /// The task of this method is to walk through the matched lines of the file as `Cells`,
+    /// with whichever search strategy was chosen for the file.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - A closure receiving each matched `Cell`, which returns `true` to stop the walk.
+    ///
+    /// # Errors
+    ///
+    /// If there is a problem opening or reading the file.
+    fn scan_cells<F: FnMut(Cell) -> bool>(&self, mut f: F) -> Result<(), Error> {
+        if self.mmap {
+            // Only the lines that contain a hit are turned into `String`s.
+            return self.scan_mapped(|n, offset, line| {
+                f(Cell::new(self.searcher.matcher(), self.look, String::from_utf8_lossy(line).into_owned(), n, offset, 1))
+            });
         }
-        c
+        self.scan_matches(false, |found| {
+            f(Cell::new(self.searcher.matcher(), self.look, found.line, found.line_number, found.offset, 1))
+        })
     }
-}
\ No newline at end of file
+
+    /// The task of this method is to walk through the matched lines of the file
+    /// using the memory-mapped strategy.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - A closure receiving the `line number`, the `byte offset` of the line
+    ///   and the raw `line` without its terminator, which returns `true` to stop the walk.
+    ///
+    /// # Errors
+    ///
+    /// If there is a problem opening or mapping the file.
+    fn scan_mapped<F: FnMut(usize, u64, &[u8]) -> bool>(&self, mut f: F) -> Result<(), Error> {
+        let Some(map) = mmap::map_file(&self.path)? else { return Ok(()) };
+        let (hay, before) = self.select(&map);
+        for (n, line) in mmap::matched_lines(hay, self.searcher.matcher()) {
+            let offset = line.as_ptr() as usize - map.as_ptr() as usize;
+            if f(before + n, offset as u64, line) {
+                return Ok(());
+            }
+        }
+        self.reach_mapped(&map);
+        Ok(())
+    }
+
+    /// This method remembers the end of the complete lines of a mapped file that was read to its end.
+    fn reach_mapped(&self, map: &[u8]) {
+        if self.searcher.selection().is_none() {
+            self.reached.set(Some(mmap::complete_lines(map)));
+        }
+    }
+
+    /// This method cuts the selected part out of the contents of the file, if there is a `Selection`.
+    ///
+    /// # Returns
+    ///
+    /// The selected bytes, and the number of lines before them.
+    fn select<'m>(&self, hay: &'m [u8]) -> (&'m [u8], usize) {
+        match self.searcher.selection() {
+            Some(selection) => selection.slice(hay),
+            None => (hay, 0),
+        }
+    }
+
+    /// The task of this method is to walk through the matched lines of the file (or of its `Selection`)
+    /// with the `Searcher`. Lines that are not valid UTF-8 are skipped but still counted.
+    ///
+    /// # Arguments
+    ///
+    /// * `passthru` - Whether every line is walked through, the ones without a match having no spans.
+    /// * `f` - A closure receiving each `Match`, which returns `true` to stop the walk.
+    ///
+    /// # Errors
+    ///
+    /// If there is a problem opening or reading the file.
+    fn scan_matches<F: FnMut(Match) -> bool>(&self, passthru: bool, mut f: F) -> Result<(), Error> {
+        let mut matches = self.searcher.search_path(&self.path)?.passthru(passthru);
+        for found in matches.by_ref() {
+            if f(found?) {
+                return Ok(());
+            }
+        }
+        self.reached.set(matches.reached());
+        Ok(())
+    }
+}
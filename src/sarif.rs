@@ -0,0 +1,165 @@
+//! This module is related to the **SARIF** output of `lenz` (`--format sarif`).
+//! SARIF 2.1.0 is the format read by code-scanning dashboards in CI.
+//! The whole run is printed as a single document: the query is its rule,
+//! each match is a result, and the corrupted files are reported as notifications.
+
+
+use std::io::{ Error, Write, };
+use crate::json;
+use crate::cells::{ Cell, };
+use crate::errors::{ LenzError, };
+use crate::printer::{ Printer, };
+
+
+/// The address of the SARIF 2.1.0 schema.
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+
+/// The task of this function is to describe one match as a SARIF result.
+///
+/// # Arguments
+///
+/// * `rule` - The id of the rule, which is the query.
+/// * `path` - The path of the file.
+/// * `lineno` - The line number of the match.
+/// * `columns` - The first column of the match and the column right after it,
+///   counted in UTF-16 code units from `1` as SARIF expects.
+/// * `line` - The matched line, used as the snippet.
+///
+/// # Returns
+///
+/// A `String` containing one JSON object.
+pub fn result(rule: &str, path: &str, lineno: usize, columns: (usize, usize), line: &str) -> String {
+    format!(
+        "{{\"ruleId\":{},\"ruleIndex\":0,\"level\":\"warning\",\"message\":{{\"text\":{}}},\
+\"locations\":[{{\"physicalLocation\":{{\"artifactLocation\":{{\"uri\":{}}},\
+\"region\":{{\"startLine\":{},\"startColumn\":{},\"endColumn\":{},\"snippet\":{{\"text\":{}}}}}}}}}]}}",
+        json::string(rule),
+        json::string(&format!("Found '{}'.", rule)),
+        json::string(&uri(path)),
+        lineno,
+        columns.0,
+        columns.1,
+        json::string(line),
+    )
+}
+
+
+/// The task of this function is to put the results of a run together into a SARIF document.
+///
+/// # Arguments
+///
+/// * `rule` - The id of the rule, which is the query.
+/// * `results` - The results made by `result`.
+/// * `errors` - The messages of the files that could not be searched.
+/// * `notes` - Other messages about the run, like truncated results.
+///
+/// # Returns
+///
+/// A `String` containing the whole document.
+pub fn report(rule: &str, results: &[String], errors: &[String], notes: &[String]) -> String {
+    let notification = |level: &str, text: &String| {
+        format!("{{\"level\":\"{}\",\"message\":{{\"text\":{}}}}}", level, json::string(text))
+    };
+    let notifications: Vec<String> = errors.iter().map(|e| notification("error", e))
+                                           .chain(notes.iter().map(|n| notification("note", n)))
+                                           .collect();
+    format!(
+        "{{\"$schema\":\"{}\",\"version\":\"2.1.0\",\"runs\":[{{\"tool\":{{\"driver\":{{\
+\"name\":\"lenz\",\"version\":\"{}\",\"informationUri\":\"https://github.com/mimseyedi/lenz\",\
+\"rules\":[{{\"id\":{},\"shortDescription\":{{\"text\":{}}}}}]}}}},\
+\"invocations\":[{{\"executionSuccessful\":{},\"toolExecutionNotifications\":[{}]}}],\
+\"columnKind\":\"utf16CodeUnits\",\"results\":[{}]}}]}}",
+        SCHEMA,
+        env!("CARGO_PKG_VERSION"),
+        json::string(rule),
+        json::string(&format!("Occurrences of '{}'.", rule)),
+        errors.is_empty(),
+        notifications.join(","),
+        results.join(","),
+    )
+}
+
+
+/// The task of this function is to turn a path into the URI of a SARIF artifact.
+/// Relative paths stay relative (to the directory `lenz` was run in),
+/// and absolute paths become `file://` URIs.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file.
+///
+/// # Returns
+///
+/// The percent-encoded URI.
+pub fn uri(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    let mut uri = String::with_capacity(path.len());
+    if path.starts_with('/') {
+        uri.push_str("file://");
+    }
+    for b in path.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => uri.push(b as char),
+            _ => uri.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    uri
+}
+
+
+/// The task of this function is to describe each match of a line as a SARIF result.
+///
+/// # Arguments
+///
+/// * `rule` - The query, which is the only rule of the run.
+/// * `path` - The path of the file the line belongs to.
+/// * `cell` - The line.
+///
+/// # Returns
+///
+/// A `Vec<String>` containing one JSON object per match.
+pub fn results(rule: &str, path: &str, cell: &Cell) -> Vec<String> {
+    let line = cell.line();
+    let utf16 = |x: usize| line[..x].encode_utf16().count() + 1;
+    cell.spans().into_iter().map(|(x, n)| {
+        result(rule, path, cell.lineno(), (utf16(x), utf16(x + n)), line)
+    }).collect()
+}
+
+
+/// The `Printer` of the SARIF format, which displays the whole document when the search is over.
+pub struct Sarif<W: Write> {
+    out: W,
+    rule: String,
+    results: Vec<String>,
+    errors: Vec<String>,
+}
+
+
+impl<W: Write> Sarif<W> {
+    /// `Sarif` Constructor.
+    pub fn new(out: W, rule: &str) -> Self {
+        Self { out, rule: rule.to_string(), results: Vec::new(), errors: Vec::new() }
+    }
+}
+
+
+impl<W: Write> Printer for Sarif<W> {
+    fn matched(&mut self, path: &str, cell: &Cell) -> Result<(), Error> {
+        self.results.extend(results(&self.rule, path, cell));
+        Ok(())
+    }
+
+    fn error(&mut self, error: &LenzError) -> Result<(), Error> {
+        self.errors.push(error.plain());
+        Ok(())
+    }
+
+    fn finish(&mut self, truncated: Option<&str>) -> Result<(), Error> {
+        let notes: Vec<String> = truncated.map(|limits| format!("Results were truncated: {}.", limits))
+                                          .into_iter()
+                                          .collect();
+        writeln!(self.out, "{}", report(&self.rule, &self.results, &self.errors, &notes))
+    }
+}
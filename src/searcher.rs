@@ -0,0 +1,341 @@
+//! This module is the **library** interface of `lenz`, for programs that embed it.
+//! A `SearcherBuilder` collects the settings of a search and builds a `Searcher`,
+//! which searches files (or any other reader) and hands back their matches as `Match` values
+//! instead of displaying them. Nothing in here prints or exits.
+
+
+use std::fs::{ File, };
+use std::io::{ BufRead, BufReader, Error, Read, Take, };
+use crate::{ read_file, };
+use crate::matcher::{ Matcher, };
+use crate::range::{ Selection, };
+
+
+/// How the case of the letters is taken into account.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum CaseMode {
+    /// Letters only match letters of the same case.
+    #[default]
+    Sensitive,
+    /// Letters match letters of either case.
+    Insensitive,
+    /// Insensitive, unless the query contains an uppercase letter.
+    Smart,
+}
+
+
+impl CaseMode {
+    /// This method returns the name of the mode, as it is written in a config file.
+    pub fn name(&self) -> &'static str {
+        match self {
+            CaseMode::Sensitive => "sensitive",
+            CaseMode::Insensitive => "insensitive",
+            CaseMode::Smart => "smart",
+        }
+    }
+
+    /// This method returns whether a query should be searched case-insensitively.
+    pub fn ignore_case(&self, query: &str) -> bool {
+        match self {
+            CaseMode::Sensitive => false,
+            CaseMode::Insensitive => true,
+            CaseMode::Smart => !query.chars().any(char::is_uppercase),
+        }
+    }
+}
+
+
+/// A matched line, along with where it was found and where the query is in it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Match {
+    /// The path of the file (or the name given to the reader).
+    pub path: String,
+    /// The true line number in the file, counted from `1`.
+    pub line_number: usize,
+    /// The byte offset of the start of the line in the file.
+    pub offset: u64,
+    /// The line, without its terminator.
+    pub line: String,
+    /// The `(start, len)` pairs of the matches in the line, in bytes.
+    pub spans: Vec<(usize, usize)>,
+}
+
+
+/// This structure collects the settings of a search, to build a `Searcher`.
+///
+/// ```
+/// use lenz::searcher::{ CaseMode, SearcherBuilder, };
+///
+/// let searcher = SearcherBuilder::new("lenz")
+///     .case_mode(CaseMode::Insensitive)
+///     .max_count(Some(10))
+///     .build();
+/// let found: Vec<_> = searcher.search_reader("notes", "Lenz\nno\nlenz".as_bytes())
+///                             .collect::<Result<_, _>>()
+///                             .unwrap();
+/// assert_eq!(found.len(), 2);
+/// ```
+pub struct SearcherBuilder {
+    query: String,
+    case_mode: CaseMode,
+    word: bool,
+    max_count: Option<usize>,
+    selection: Option<Selection>,
+}
+
+
+impl SearcherBuilder {
+    /// `SearcherBuilder` Constructor.
+    /// By default, the search is case-sensitive, not limited to whole words and not limited at all.
+    pub fn new(query: &str) -> Self {
+        Self {
+            query: query.to_string(),
+            case_mode: CaseMode::default(),
+            word: false,
+            max_count: None,
+            selection: None,
+        }
+    }
+
+    /// This method sets how the case of the letters is taken into account.
+    pub fn case_mode(mut self, case_mode: CaseMode) -> Self {
+        self.case_mode = case_mode;
+        self
+    }
+
+    /// This method sets whether only whole words should match.
+    pub fn word(mut self, word: bool) -> Self {
+        self.word = word;
+        self
+    }
+
+    /// This method sets the maximum number of matched lines of each search.
+    pub fn max_count(mut self, max_count: Option<usize>) -> Self {
+        self.max_count = max_count;
+        self
+    }
+
+    /// This method sets the part of each file (or reader) to be searched.
+    pub fn selection(mut self, selection: Option<Selection>) -> Self {
+        self.selection = selection;
+        self
+    }
+
+    /// The task of this method is to compile the query and build the `Searcher`.
+    pub fn build(self) -> Searcher {
+        let ignore_case = self.case_mode.ignore_case(&self.query);
+        Searcher {
+            matcher: Matcher::with_word(&self.query, ignore_case, self.word),
+            max_count: self.max_count,
+            selection: self.selection,
+        }
+    }
+}
+
+
+/// A compiled search that can be run on any number of files or readers.
+pub struct Searcher {
+    matcher: Matcher,
+    max_count: Option<usize>,
+    selection: Option<Selection>,
+}
+
+
+impl Searcher {
+    /// `matcher` attr getter.
+    pub fn matcher(&self) -> &Matcher {
+        &self.matcher
+    }
+
+    /// `max_count` attr getter.
+    pub fn max_count(&self) -> Option<usize> {
+        self.max_count
+    }
+
+    /// `selection` attr getter.
+    pub fn selection(&self) -> Option<Selection> {
+        self.selection
+    }
+
+    /// The task of this method is to search a file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file.
+    ///
+    /// # Returns
+    ///
+    /// An iterator of the matches of the file, which reads the file as it goes.
+    ///
+    /// # Errors
+    ///
+    /// If the file cannot be opened, or the start of the `Selection` cannot be reached.
+    pub fn search_path(&self, path: &str) -> Result<SearchMatches<'_, BufReader<File>>, Error> {
+        let mut reader = read_file(path)?;
+        let start = match self.selection {
+            Some(selection) => selection.seek(&mut reader)?,
+            None => (0, 0),
+        };
+        let mut matches = SearchMatches::new(self, path, reader);
+        matches.start_at(start);
+        Ok(matches)
+    }
+
+    /// The task of this method is to search anything that can be read.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The name given to the reader, which is placed in each `Match`.
+    /// * `reader` - What is searched.
+    ///
+    /// # Returns
+    ///
+    /// An iterator of the matches of the reader, which reads it as it goes.
+    pub fn search_reader<R: Read>(&self, path: &str, reader: R) -> SearchMatches<'_, BufReader<R>> {
+        SearchMatches::new(self, path, BufReader::new(reader))
+    }
+}
+
+
+/// An iterator over the matches of a search.
+/// A failed read is returned once, and then the iterator ends.
+/// Lines that are not valid UTF-8 are skipped but still counted.
+pub struct SearchMatches<'s, R: BufRead> {
+    searcher: &'s Searcher,
+    path: String,
+    reader: Take<R>,
+    /// Whether every line is handed back, and not only the matched ones.
+    passthru: bool,
+    /// Whether the part before the `Selection` was skipped.
+    started: bool,
+    done: bool,
+    lineno: usize,
+    offset: u64,
+    /// The number of complete lines read so far, and the byte offset after them.
+    complete: (usize, u64),
+    /// Whether the end of the reader was reached.
+    ended: bool,
+    last: usize,
+    found: usize,
+    buf: Vec<u8>,
+}
+
+
+impl<'s, R: BufRead> SearchMatches<'s, R> {
+    /// `SearchMatches` Constructor.
+    fn new(searcher: &'s Searcher, path: &str, reader: R) -> Self {
+        Self {
+            searcher,
+            path: path.to_string(),
+            reader: reader.take(u64::MAX),
+            passthru: false,
+            started: false,
+            done: false,
+            lineno: 0,
+            offset: 0,
+            complete: (0, 0),
+            ended: false,
+            last: usize::MAX,
+            found: 0,
+            buf: Vec::new(),
+        }
+    }
+
+    /// This method makes the search hand back every line, like page view shows them:
+    /// the lines without a match have no spans, and the search stops right before the first matched line over `max_count`.
+    pub fn passthru(mut self, passthru: bool) -> Self {
+        self.passthru = passthru;
+        self
+    }
+
+    /// This method returns where the end of the reader was reached, once the iterator ended there:
+    /// the number of complete lines and the byte offset after them (a last line without
+    /// its terminator is left out). It is `None` if the search stopped before the end.
+    pub fn reached(&self) -> Option<(usize, u64)> {
+        self.ended.then_some(self.complete)
+    }
+
+    /// This method skips the part of the reader before the `Selection`, if there is one.
+    fn start(&mut self) -> Result<(), Error> {
+        let start = match self.searcher.selection {
+            Some(selection) => selection.skip(self.reader.get_mut())?,
+            None => (0, 0),
+        };
+        self.start_at(start);
+        Ok(())
+    }
+
+    /// This method starts the search after `start`, the lines and bytes before the `Selection`,
+    /// once the reader was moved there.
+    fn start_at(&mut self, start: (usize, u64)) {
+        self.started = true;
+        (self.lineno, self.offset) = start;
+        self.complete = start;
+        if let Some(selection) = self.searcher.selection {
+            self.reader.set_limit(selection.byte_limit());
+            self.last = selection.last_line();
+        }
+    }
+
+    /// This method reads lines until the next matched one (or the next line, with `passthru`).
+    fn next_match(&mut self) -> Result<Option<Match>, Error> {
+        if !self.started {
+            self.start()?;
+        }
+        let matcher = &self.searcher.matcher;
+        let full = |found: usize| self.searcher.max_count.is_some_and(|m| found >= m);
+        while self.lineno < self.last {
+            if full(self.found) && !self.passthru {
+                return Ok(None);
+            }
+            self.buf.clear();
+            let read = match self.reader.read_until(b'\n', &mut self.buf)? {
+                0 => {
+                    // A `Selection` of bytes may end before the reader does.
+                    self.ended = self.reader.limit() > 0;
+                    return Ok(None);
+                },
+                read => read,
+            };
+            let offset = self.offset;
+            self.lineno += 1;
+            self.offset += read as u64;
+            if self.buf.ends_with(b"\n") {
+                self.complete = (self.lineno, self.offset);
+            }
+            let line = self.buf.strip_suffix(b"\n").unwrap_or(&self.buf);
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+            // Invalid bytes are replaced, as the memory-mapped search does.
+            let line = String::from_utf8_lossy(line);
+            let spans: Vec<(usize, usize)> = matcher.find_iter(&line).collect();
+            if !spans.is_empty() && full(self.found) {
+                return Ok(None);
+            }
+            if !spans.is_empty() || self.passthru {
+                self.found += usize::from(!spans.is_empty());
+                return Ok(Some(Match {
+                    path: self.path.clone(),
+                    line_number: self.lineno,
+                    offset,
+                    line: line.into_owned(),
+                    spans,
+                }));
+            }
+        }
+        Ok(None)
+    }
+}
+
+
+impl<R: BufRead> Iterator for SearchMatches<'_, R> {
+    type Item = Result<Match, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let result = self.next_match().transpose();
+        self.done = !matches!(result, Some(Ok(_)));
+        result
+    }
+}
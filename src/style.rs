@@ -2,12 +2,251 @@
 //! This module uses `ANSI escape codes` for styling.
 //! Naturally, this approach can only be used in terminals or shells that support it.
 //! Like `bash`.
+//! How the output looks is held by a `Look`, which is given to each part of the output when it is built:
+//! whether it is styled at all (not when the output is not a terminal, for example), the `Theme`,
+//! and the depth the colors are downgraded to.
+//! Besides the 8 basic colors, a `Color` can be one of the 256 indexed colors or a 24-bit color,
+//! which is downgraded to what the terminal supports (`ColorDepth::detect`).
 
 
-use crate::{ find_all, };
+use std::env;
+use std::io::{ stderr, stdout, IsTerminal, };
+use std::fmt;
+use crate::matcher::{ Matcher, };
+use crate::hyperlink::{ PathCache, };
+use crate::theme::{ Slot, Theme, };
+use crate::width::{ TAB_WIDTH, };
+
+
+/// The names of the basic colors, in the order of their codes.
+const NAMES: [&str; 8] = ["black", "red", "green", "yellow", "blue", "magenta", "cyan", "white"];
+/// The look of the first 16 indexed colors (the basic colors, then their bright versions), as in `xterm`.
+const PALETTE: [(u8, u8, u8); 16] = [
+    (0, 0, 0), (205, 0, 0), (0, 205, 0), (205, 205, 0), (0, 0, 238), (205, 0, 205), (0, 205, 205), (229, 229, 229),
+    (127, 127, 127), (255, 0, 0), (0, 255, 0), (255, 255, 0), (92, 92, 255), (255, 0, 255), (0, 255, 255), (255, 255, 255),
+];
+/// The levels of red, green and blue in the 6x6x6 cube of the indexed colors.
+const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+
+/// When to color the output, as chosen with `--color`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ColorChoice {
+    /// Only when the output is a terminal and `NO_COLOR` is not set.
+    Auto,
+    Always,
+    Never,
+}
+
+
+impl ColorChoice {
+    /// The task of this method is to parse the value of `--color`.
+    ///
+    /// # Returns
+    ///
+    /// `Some(ColorChoice)` for `auto`, `always` or `never`, otherwise `None`.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "auto" => Some(ColorChoice::Auto),
+            "always" => Some(ColorChoice::Always),
+            "never" => Some(ColorChoice::Never),
+            _ => None,
+        }
+    }
+
+    /// This method returns the name of the choice, as it is given to `--color`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ColorChoice::Auto => "auto",
+            ColorChoice::Always => "always",
+            ColorChoice::Never => "never",
+        }
+    }
+
+    /// This method decides whether the output should be colored.
+    pub fn enabled(&self) -> bool {
+        self.enabled_on(stdout().is_terminal())
+    }
+
+    /// This method decides whether an output that is a terminal or not (`terminal`) should be colored.
+    pub fn enabled_on(&self, terminal: bool) -> bool {
+        match self {
+            ColorChoice::Auto => terminal && env::var_os("NO_COLOR").is_none(),
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
+}
+
+
+/// How many colors the terminal can display.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ColorDepth {
+    /// The 8 basic colors.
+    Basic,
+    /// The 256 indexed colors.
+    Ansi256,
+    /// Any 24-bit color.
+    TrueColor,
+}
+
+
+impl ColorDepth {
+    /// Every depth, from the fewest colors to the most.
+    pub const ALL: [ColorDepth; 3] = [ColorDepth::Basic, ColorDepth::Ansi256, ColorDepth::TrueColor];
+
+    /// The task of this function is to find the depth of the terminal from `COLORTERM` and `TERM`.
+    ///
+    /// # Returns
+    ///
+    /// `TrueColor` if `COLORTERM` is `truecolor` or `24bit` (or `TERM` says so), `Ansi256` if `TERM`
+    /// names a 256-color terminal, and `Basic` otherwise.
+    pub fn detect() -> Self {
+        let colorterm = env::var("COLORTERM").unwrap_or_default();
+        let term = env::var("TERM").unwrap_or_default();
+        Self::from_env(&colorterm, &term)
+    }
+
+    /// This function is `detect`, for the given values of `COLORTERM` and `TERM`.
+    pub fn from_env(colorterm: &str, term: &str) -> Self {
+        if matches!(colorterm, "truecolor" | "24bit") || ["truecolor", "24bit", "direct"].iter().any(|t| term.contains(t)) {
+            ColorDepth::TrueColor
+        } else if term.contains("256") {
+            ColorDepth::Ansi256
+        } else {
+            ColorDepth::Basic
+        }
+    }
+}
+
+
+/// A color of the text or of its background.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Color {
+    /// One of the 8 basic colors, from `0` (black) to `7` (white).
+    Basic(u8),
+    /// One of the 256 indexed colors.
+    Fixed(u8),
+    /// A 24-bit color.
+    Rgb(u8, u8, u8),
+}
+
+
+impl Color {
+    pub const BLACK: Color = Color::Basic(0);
+    pub const RED: Color = Color::Basic(1);
+    pub const GREEN: Color = Color::Basic(2);
+    pub const YELLOW: Color = Color::Basic(3);
+    pub const BLUE: Color = Color::Basic(4);
+    pub const MAGENTA: Color = Color::Basic(5);
+    pub const CYAN: Color = Color::Basic(6);
+    pub const WHITE: Color = Color::Basic(7);
+
+    /// The task of this function is to parse a color.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The name of a basic color (like `yellow`), an index from `0` to `255`, or `#RRGGBB`.
+    ///
+    /// # Returns
+    ///
+    /// `Some(Color)` if the value is valid, otherwise `None`.
+    pub fn parse(value: &str) -> Option<Self> {
+        if let Some(i) = NAMES.iter().position(|n| *n == value) {
+            return Some(Color::Basic(i as u8));
+        }
+        if let Some(hex) = value.strip_prefix('#') {
+            if hex.len() != 6 || !hex.is_ascii() {
+                return None;
+            }
+            let part = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+            return Some(Color::Rgb(part(0)?, part(2)?, part(4)?));
+        }
+        value.parse().ok().map(Color::Fixed)
+    }
+
+    /// This method returns the red, green and blue levels of the color, as `xterm` displays it.
+    pub fn rgb(&self) -> (u8, u8, u8) {
+        match *self {
+            Color::Basic(n) => PALETTE[usize::from(n & 7)],
+            Color::Fixed(n @ 0..=15) => PALETTE[usize::from(n)],
+            Color::Fixed(n @ 16..=231) => {
+                let n = usize::from(n - 16);
+                (CUBE[n / 36], CUBE[n / 6 % 6], CUBE[n % 6])
+            },
+            Color::Fixed(n) => {
+                let level = 8 + 10 * (n - 232);
+                (level, level, level)
+            },
+            Color::Rgb(r, g, b) => (r, g, b),
+        }
+    }
+
+    /// The task of this method is to turn the color into the nearest one that a terminal of `depth` can display.
+    pub fn downgrade(&self, depth: ColorDepth) -> Self {
+        match (*self, depth) {
+            (_, ColorDepth::TrueColor) | (Color::Basic(_), _) | (Color::Fixed(_), ColorDepth::Ansi256) => *self,
+            (Color::Rgb(r, g, b), ColorDepth::Ansi256) => Color::Fixed(to_ansi256(r, g, b)),
+            (Color::Fixed(n @ 0..=15), ColorDepth::Basic) => Color::Basic(n & 7),
+            (color, ColorDepth::Basic) => {
+                let (r, g, b) = color.rgb();
+                let distance = |&(i, &(pr, pg, pb)): &(usize, &(u8, u8, u8))| {
+                    let d = |x: u8, y: u8| (i32::from(x) - i32::from(y)).pow(2);
+                    (d(r, pr) + d(g, pg) + d(b, pb), i)
+                };
+                let nearest = PALETTE[..8].iter().enumerate().min_by_key(distance).map_or(0, |(i, _)| i);
+                Color::Basic(nearest as u8)
+            },
+        }
+    }
+
+    /// This method returns the parameters of the `SGR` escape code of the color, like `31` or `38;5;208`.
+    ///
+    /// # Arguments
+    ///
+    /// * `background` - Whether the color is the background's.
+    pub fn sgr(&self, background: bool) -> String {
+        let base = if background { 40 } else { 30 };
+        match *self {
+            Color::Basic(n) => (base + (n & 7)).to_string(),
+            Color::Fixed(n) => format!("{};5;{}", base + 8, n),
+            Color::Rgb(r, g, b) => format!("{};2;{};{};{}", base + 8, r, g, b),
+        }
+    }
+}
+
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Color::Basic(n) => write!(f, "{}", NAMES[usize::from(n & 7)]),
+            Color::Fixed(n) => write!(f, "{}", n),
+            Color::Rgb(r, g, b) => write!(f, "#{:02x}{:02x}{:02x}", r, g, b),
+        }
+    }
+}
+
+
+/// This function returns the index of the 256 colors that is nearest to a 24-bit color.
+fn to_ansi256(r: u8, g: u8, b: u8) -> u8 {
+    if r == g && g == b {
+        return match r {
+            0..=7 => 16,
+            249..=255 => 231,
+            _ => 232 + ((r - 8) / 10).min(23),
+        };
+    }
+    let level = |v: u8| match v {
+        0..=47 => 0,
+        48..=114 => 1,
+        _ => (v - 35) / 40,
+    };
+    16 + 36 * level(r) + 6 * level(g) + level(b)
+}
 
 
 /// An enum, to define various colors and styles.
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum ANSIStyle {
     Reset,
     // Styles
@@ -41,6 +280,7 @@ pub enum ANSIStyle {
 impl ANSIStyle {
     /// The task of this method is to convert enum options
     /// into literal strings corresponding to `ANSI escape codes`.
+    /// Whether the output is styled at all is up to the `Look` (see `Look::ansi`).
     pub fn as_str(&self) -> &'static str {
         match *self {
             ANSIStyle::Reset     => "\x1b[0m",
@@ -71,11 +311,124 @@ impl ANSIStyle {
             ANSIStyle::BGDef     => "\x1b[49m",
         }
     }
+
+    /// The task of this method is to find the style of an `ANSI escape code`.
+    ///
+    /// # Returns
+    ///
+    /// `Some(ANSIStyle)` if the code is one of the styles, otherwise `None`.
+    pub fn from_code(code: &str) -> Option<Self> {
+        use ANSIStyle::*;
+        [
+            Reset, Bold, Italic, Underline, Blink,
+            FGBlack, FGRed, FGGreen, FGYellow, FGBlue, FGMagenta, FGCyan, FGWhite, FGDef,
+            BGBlack, BGRed, BGGreen, BGYellow, BGBlue, BGMagenta, BGCyan, BGWhite, BGDef,
+        ].into_iter().find(|style| style.as_str() == code)
+    }
+
+    /// This method returns the CSS class standing for the style in HTML reports.
+    /// `Reset` has no class, since it closes the styled elements instead.
+    pub fn css_class(&self) -> &'static str {
+        match *self {
+            ANSIStyle::Reset     => "",
+            // Styles
+            ANSIStyle::Bold      => "bold",
+            ANSIStyle::Italic    => "italic",
+            ANSIStyle::Underline => "underline",
+            ANSIStyle::Blink     => "blink",
+            // Foreground colors
+            ANSIStyle::FGBlack   => "fg-black",
+            ANSIStyle::FGRed     => "fg-red",
+            ANSIStyle::FGGreen   => "fg-green",
+            ANSIStyle::FGYellow  => "fg-yellow",
+            ANSIStyle::FGBlue    => "fg-blue",
+            ANSIStyle::FGMagenta => "fg-magenta",
+            ANSIStyle::FGCyan    => "fg-cyan",
+            ANSIStyle::FGWhite   => "fg-white",
+            ANSIStyle::FGDef     => "fg-def",
+            // Background colors
+            ANSIStyle::BGBlack   => "bg-black",
+            ANSIStyle::BGRed     => "bg-red",
+            ANSIStyle::BGGreen   => "bg-green",
+            ANSIStyle::BGYellow  => "bg-yellow",
+            ANSIStyle::BGBlue    => "bg-blue",
+            ANSIStyle::BGMagenta => "bg-magenta",
+            ANSIStyle::BGCyan    => "bg-cyan",
+            ANSIStyle::BGWhite   => "bg-white",
+            ANSIStyle::BGDef     => "bg-def",
+        }
+    }
+}
+
+
+/// How the output looks: whether it is styled, with which `Theme` and colors,
+/// how wide the tabs of the lines are and where their hyperlinks lead.
+/// Each printer, cell and pager is given its own, so that two of them can look different.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Look {
+    /// Whether escape codes are written at all.
+    pub colors: bool,
+    /// The depth the colors are downgraded to.
+    pub depth: ColorDepth,
+    pub theme: Theme,
+    /// The distance between two tab stops.
+    pub tab_width: usize,
+    /// The URL template of the hyperlinks (see `hyperlink`), empty when they are off.
+    pub hyperlink_format: String,
+    /// The absolute paths of the files the hyperlinks lead to.
+    pub paths: PathCache,
+}
+
+
+impl Default for Look {
+    /// The look of a terminal that displays every color, with the default theme and no hyperlinks.
+    fn default() -> Self {
+        Self {
+            colors: true,
+            depth: ColorDepth::TrueColor,
+            theme: Theme::DEFAULT,
+            tab_width: TAB_WIDTH,
+            hyperlink_format: String::new(),
+            paths: PathCache::default(),
+        }
+    }
+}
+
+
+impl Look {
+    /// This function returns the default look, without any styling.
+    pub fn plain() -> Self {
+        Self { colors: false, ..Self::default() }
+    }
+
+    /// This function returns the look of the messages written to the standard error,
+    /// which is only colored when `choice` allows it for `stderr`.
+    pub fn for_stderr(choice: ColorChoice) -> Self {
+        Self { colors: choice.enabled_on(stderr().is_terminal()), depth: ColorDepth::detect(), ..Self::default() }
+    }
+
+    /// This method returns the escape code of an `ANSIStyle`, or an empty string if styling is off.
+    pub fn ansi(&self, style: ANSIStyle) -> &'static str {
+        if self.colors { style.as_str() } else { "" }
+    }
+
+    /// The task of this method is to style a text with a slot of the theme.
+    ///
+    /// # Returns
+    ///
+    /// The styled text, or the text itself if the style (or styling) is off.
+    pub fn paint(&self, slot: Slot, text: &str) -> String {
+        if !self.colors {
+            return text.to_string();
+        }
+        self.theme.style(slot).paint(text, self.depth)
+    }
 }
 
 
 /// The task of this function is to `highlight` the query in a string.
-/// This is done with the help of other functions in the main module.
+/// This is done with the help of a `Matcher` compiled for this call only, and with the default `Look`;
+/// for repeated highlighting, use `hg_matches` with a shared `Matcher`.
 ///
 /// # Arguments
 ///
@@ -88,20 +441,48 @@ impl ANSIStyle {
 /// The output is a new `String` highlighted.
 /// If there is no query, the `original` string itself will be returned.
 pub fn hg_query(query: &str, text: &str, ignore_case: bool) -> String {
-    match find_all(query, text, ignore_case) {
-        Some(vec) => {
-            let mut hg_str = String::new();
-            let mut l = 0;
-            for (x, n) in vec {
-                hg_str.push_str(&text[l..x]);
-                hg_str.push_str(ANSIStyle::BGRed.as_str());
-                hg_str.push_str(&text[x..x+n]);
-                hg_str.push_str(ANSIStyle::Reset.as_str());
-                l = x + query.len();
-            }
-            hg_str.push_str(&text[l..]);
-            hg_str
-        }
-        None => text.to_string(),
+    hg_matches(&Look::default(), &Matcher::new(query, ignore_case), text)
+}
+
+
+/// The task of this function is to `highlight` the matches of a `Matcher` in a string,
+/// with the `match` style of the theme.
+///
+/// # Arguments
+///
+/// * `look` - How the matches are styled.
+/// * `matcher` - The compiled query.
+/// * `text` - Text that can contain a substring.
+///
+/// # Returns
+///
+/// The output is a new `String` highlighted.
+/// If there is no match, the `original` string itself will be returned.
+pub fn hg_matches(look: &Look, matcher: &Matcher, text: &str) -> String {
+    hg_spans(look, text, matcher.find_iter(text))
+}
+
+
+/// The task of this function is to `highlight` the given parts of a string,
+/// with the `match` style of the theme.
+///
+/// # Arguments
+///
+/// * `look` - How the parts are styled.
+/// * `text` - The string.
+/// * `spans` - The `(start, len)` pairs of the parts, in bytes and in order.
+///
+/// # Returns
+///
+/// The output is a new `String` highlighted.
+pub fn hg_spans<I: IntoIterator<Item = (usize, usize)>>(look: &Look, text: &str, spans: I) -> String {
+    let mut hg_str = String::with_capacity(text.len());
+    let mut l = 0;
+    for (x, n) in spans {
+        hg_str.push_str(&text[l..x]);
+        hg_str.push_str(&look.paint(Slot::Match, &text[x..x+n]));
+        l = x + n;
     }
-}
\ No newline at end of file
+    hg_str.push_str(&text[l..]);
+    hg_str
+}
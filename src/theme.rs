@@ -0,0 +1,397 @@
+//! This module is related to the **themes** of `lenz`, which decide the colors of the output.
+//! A `Theme` holds a `Style` for each of its named slots (`match`, `lineno`, `matched-lineno`,
+//! `path`, `count`, `separator`, `error`, `removed` and `added`), so that nothing in the output
+//! is hard-coded to a color that cannot be seen on a light terminal or told apart by a colorblind user.
+//!
+//! A theme is chosen by name with `--theme`, and each slot can then be changed with
+//! `--colors`, written as `SLOT:fg:COLOR`, `SLOT:bg:COLOR`, `SLOT:style:ATTR`, `SLOT:none`,
+//! or `SLOT:STYLE` for a whole style at once (like `match:bold+underline+fg=#ff8800+bg=236`).
+//! A color is the name of a basic color, an index of the 256 colors or `#RRGGBB`.
+
+
+use std::fmt;
+use crate::style::{ ANSIStyle, Color, ColorDepth, };
+
+
+/// The names of the built-in themes.
+pub const THEMES: [&str; 3] = ["default", "light", "colorblind"];
+
+
+/// A part of the output that can be styled by a `Theme`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Slot {
+    /// The matches in a line.
+    Match,
+    /// The line number of a line.
+    Lineno,
+    /// The line number of a matched line, in page view.
+    MatchedLineno,
+    /// The path of a file.
+    Path,
+    /// The number of matches of a file.
+    Count,
+    /// The colons between the line number, the column and the byte offset.
+    Separator,
+    /// The errors and the errors report.
+    Error,
+    /// The `-` before a line that no longer matches, with `--diff`.
+    Removed,
+    /// The `+` before a line that matches since the previous run, with `--diff`.
+    Added,
+}
+
+
+impl Slot {
+    /// Every slot, in the order they are kept in a `Theme`.
+    pub const ALL: [Slot; 9] = [
+        Slot::Match, Slot::Lineno, Slot::MatchedLineno, Slot::Path,
+        Slot::Count, Slot::Separator, Slot::Error, Slot::Removed, Slot::Added,
+    ];
+
+    /// This method returns the name of the slot, as it is given to `--colors`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Slot::Match => "match",
+            Slot::Lineno => "lineno",
+            Slot::MatchedLineno => "matched-lineno",
+            Slot::Path => "path",
+            Slot::Count => "count",
+            Slot::Separator => "separator",
+            Slot::Error => "error",
+            Slot::Removed => "removed",
+            Slot::Added => "added",
+        }
+    }
+
+    /// This method returns the slot of a name, if there is one.
+    pub fn parse(name: &str) -> Option<Self> {
+        Slot::ALL.into_iter().find(|slot| slot.name() == name)
+    }
+
+    /// This method returns the place of the slot in a `Theme`.
+    fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+
+/// The look of a slot: its colors and its attributes, which are written as a single escape code.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Style {
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+}
+
+
+impl Style {
+    /// A style that leaves the text as it is.
+    pub const NONE: Style = Style { fg: None, bg: None, bold: false, italic: false, underline: false };
+
+    /// This function returns a style with only a foreground color.
+    pub const fn fg(color: Color) -> Self {
+        Style { fg: Some(color), ..Style::NONE }
+    }
+
+    /// This function returns a style with only a background color.
+    pub const fn bg(color: Color) -> Self {
+        Style { bg: Some(color), ..Style::NONE }
+    }
+
+    /// The task of this function is to parse a whole style written as one value,
+    /// like `bold+underline+fg=#ff8800+bg=236`.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - `+`-separated parts, each being `bold`, `italic`, `underline`,
+    ///   `fg=COLOR` or `bg=COLOR`, where `COLOR` is accepted by `Color::parse`. `none` is no style at all.
+    ///
+    /// # Returns
+    ///
+    /// `Some(Style)` if every part is valid, otherwise `None`.
+    pub fn parse(value: &str) -> Option<Self> {
+        let mut style = Style::NONE;
+        if value == "none" {
+            return Some(style);
+        }
+        for part in value.split('+') {
+            match part.split_once('=') {
+                Some(("fg", color)) => style.fg = Some(Color::parse(color)?),
+                Some(("bg", color)) => style.bg = Some(Color::parse(color)?),
+                Some(_) => return None,
+                None => *style.attr(part)? = true,
+            }
+        }
+        Some(style)
+    }
+
+    /// The task of this function is to read the parameters of an `SGR` escape code into a style,
+    /// like `1;38;5;208` (the codes `Style` writes, along with the basic ones of `ANSIStyle`).
+    ///
+    /// # Returns
+    ///
+    /// `Some(Style)` if every parameter is known, otherwise `None`.
+    pub fn from_sgr(params: &str) -> Option<Self> {
+        let mut style = Style::NONE;
+        let mut codes = params.split(';').map(|p| p.parse::<u8>().ok());
+        while let Some(code) = codes.next() {
+            match code? {
+                1 => style.bold = true,
+                3 => style.italic = true,
+                4 => style.underline = true,
+                c @ (30..=37 | 40..=47) => *style.color(c >= 40) = Some(Color::Basic(c % 10)),
+                c @ (38 | 48) => {
+                    let color = match codes.next()?? {
+                        5 => Color::Fixed(codes.next()??),
+                        2 => Color::Rgb(codes.next()??, codes.next()??, codes.next()??),
+                        _ => return None,
+                    };
+                    *style.color(c == 48) = Some(color);
+                },
+                _ => return None,
+            }
+        }
+        Some(style)
+    }
+
+    /// This method returns the parameters of the `SGR` escape code of the style, like `1;4;38;5;208`,
+    /// with its colors downgraded to `depth`.
+    pub fn sgr(&self, depth: ColorDepth) -> String {
+        let attrs = [(self.bold, "1"), (self.italic, "3"), (self.underline, "4")];
+        let mut params: Vec<String> = attrs.iter()
+                                           .filter(|(on, _)| *on)
+                                           .map(|(_, code)| code.to_string())
+                                           .collect();
+        params.extend(self.fg.map(|c| c.downgrade(depth).sgr(false)));
+        params.extend(self.bg.map(|c| c.downgrade(depth).sgr(true)));
+        params.join(";")
+    }
+
+    /// This method returns the escape code that starts the style, with its colors downgraded to `depth`
+    /// (empty if the style is empty).
+    pub fn prefix(&self, depth: ColorDepth) -> String {
+        let params = self.sgr(depth);
+        if params.is_empty() {
+            return String::new();
+        }
+        format!("\x1b[{}m", params)
+    }
+
+    /// The task of this method is to style a text, resetting the style after it.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to be styled.
+    /// * `depth` - The depth the colors are downgraded to.
+    ///
+    /// # Returns
+    ///
+    /// The styled text, or the text itself if the style is empty.
+    pub fn paint(&self, text: &str, depth: ColorDepth) -> String {
+        let prefix = self.prefix(depth);
+        if prefix.is_empty() {
+            return text.to_string();
+        }
+        format!("{}{}{}", prefix, text, ANSIStyle::Reset.as_str())
+    }
+
+    /// This method returns the attribute of a name (`bold`, `italic` or `underline`).
+    fn attr(&mut self, name: &str) -> Option<&mut bool> {
+        match name {
+            "bold" => Some(&mut self.bold),
+            "italic" => Some(&mut self.italic),
+            "underline" => Some(&mut self.underline),
+            _ => None,
+        }
+    }
+
+    /// This method returns the foreground or the background color.
+    fn color(&mut self, background: bool) -> &mut Option<Color> {
+        if background { &mut self.bg } else { &mut self.fg }
+    }
+}
+
+
+impl fmt::Display for Style {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let attrs = [(self.bold, "bold"), (self.italic, "italic"), (self.underline, "underline")];
+        let mut parts: Vec<String> = attrs.iter()
+                                          .filter(|(on, _)| *on)
+                                          .map(|(_, name)| name.to_string())
+                                          .collect();
+        parts.extend(self.fg.map(|c| format!("fg={}", c)));
+        parts.extend(self.bg.map(|c| format!("bg={}", c)));
+        if parts.is_empty() {
+            return write!(f, "none");
+        }
+        write!(f, "{}", parts.join("+"))
+    }
+}
+
+
+/// A `Style` for each `Slot`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Theme {
+    styles: [Style; 9],
+}
+
+
+impl Theme {
+    /// The theme `lenz` has always had, made for dark terminals.
+    pub const DEFAULT: Theme = Theme {
+        styles: [
+            Style::bg(Color::RED),
+            Style::fg(Color::GREEN),
+            Style::fg(Color::RED),
+            Style { fg: Some(Color::WHITE), italic: true, ..Style::NONE },
+            Style::fg(Color::GREEN),
+            Style::fg(Color::GREEN),
+            Style::fg(Color::RED),
+            Style::fg(Color::RED),
+            Style::fg(Color::GREEN),
+        ],
+    };
+
+    /// A theme for light terminals, without white text.
+    pub const LIGHT: Theme = Theme {
+        styles: [
+            Style { fg: Some(Color::BLACK), bg: Some(Color::YELLOW), ..Style::NONE },
+            Style::fg(Color::BLUE),
+            Style { fg: Some(Color::MAGENTA), bold: true, ..Style::NONE },
+            Style { italic: true, ..Style::NONE },
+            Style::fg(Color::BLUE),
+            Style::fg(Color::BLUE),
+            Style::fg(Color::RED),
+            Style::fg(Color::RED),
+            Style::fg(Color::GREEN),
+        ],
+    };
+
+    /// A theme that never tells things apart by red and green alone.
+    pub const COLORBLIND: Theme = Theme {
+        styles: [
+            Style { fg: Some(Color::BLACK), bg: Some(Color::CYAN), ..Style::NONE },
+            Style::fg(Color::BLUE),
+            Style { fg: Some(Color::YELLOW), bold: true, underline: true, ..Style::NONE },
+            Style { fg: Some(Color::WHITE), italic: true, ..Style::NONE },
+            Style::fg(Color::CYAN),
+            Style::fg(Color::BLUE),
+            Style { fg: Some(Color::MAGENTA), underline: true, ..Style::NONE },
+            Style { fg: Some(Color::MAGENTA), bold: true, ..Style::NONE },
+            Style { fg: Some(Color::BLUE), bold: true, ..Style::NONE },
+        ],
+    };
+
+    /// The task of this function is to find a built-in theme by its name.
+    ///
+    /// # Returns
+    ///
+    /// `Some(Theme)` for one of `THEMES`, otherwise `None`.
+    pub fn named(name: &str) -> Option<Self> {
+        match name {
+            "default" => Some(Theme::DEFAULT),
+            "light" => Some(Theme::LIGHT),
+            "colorblind" => Some(Theme::COLORBLIND),
+            _ => None,
+        }
+    }
+
+    /// `style` attr getter.
+    pub fn style(&self, slot: Slot) -> Style {
+        self.styles[slot.index()]
+    }
+
+    /// This method applies a `ColorSpec` to the style of its slot.
+    pub fn apply(&mut self, spec: &ColorSpec) {
+        let style = &mut self.styles[spec.slot.index()];
+        match spec.change {
+            Change::Set(new) => *style = new,
+            Change::Fg(color) => style.fg = color,
+            Change::Bg(color) => style.bg = color,
+            Change::Bold(on) => style.bold = on,
+            Change::Italic(on) => style.italic = on,
+            Change::Underline(on) => style.underline = on,
+        }
+    }
+}
+
+
+/// What a `ColorSpec` changes in the style of its slot.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Change {
+    /// The whole style is replaced.
+    Set(Style),
+    Fg(Option<Color>),
+    Bg(Option<Color>),
+    Bold(bool),
+    Italic(bool),
+    Underline(bool),
+}
+
+
+/// A change to one slot of a theme, as given to `--colors` (like `match:bg:yellow`).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ColorSpec {
+    pub slot: Slot,
+    pub change: Change,
+}
+
+
+impl ColorSpec {
+    /// The task of this function is to parse the value of `--colors`.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - `SLOT:fg:COLOR`, `SLOT:bg:COLOR`, `SLOT:style:ATTR`, `SLOT:none` or `SLOT:STYLE`,
+    ///   where `COLOR` is accepted by `Color::parse` (or is `none`), `ATTR` is `bold`, `italic`
+    ///   or `underline` (prefixed with `no` to turn it off), and `STYLE` is a whole style
+    ///   accepted by `Style::parse`, which replaces the style of the slot.
+    ///
+    /// # Returns
+    ///
+    /// `Some(ColorSpec)` if the value is valid, otherwise `None`.
+    pub fn parse(value: &str) -> Option<Self> {
+        let (slot, rest) = value.split_once(':')?;
+        let slot = Slot::parse(slot)?;
+        let color = |c: &str| if c == "none" { Some(None) } else { Color::parse(c).map(Some) };
+        let change = match rest.split_once(':') {
+            Some(("fg", c)) => Change::Fg(color(c)?),
+            Some(("bg", c)) => Change::Bg(color(c)?),
+            Some(("style", attr)) => {
+                let (on, attr) = match attr.strip_prefix("no") {
+                    Some(attr) => (false, attr),
+                    None => (true, attr),
+                };
+                match attr {
+                    "bold" => Change::Bold(on),
+                    "italic" => Change::Italic(on),
+                    "underline" => Change::Underline(on),
+                    _ => return None,
+                }
+            },
+            Some(_) => return None,
+            None => Change::Set(Style::parse(rest)?),
+        };
+        Some(ColorSpec { slot, change })
+    }
+}
+
+
+impl fmt::Display for ColorSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let color = |c: Option<Color>| c.map_or(String::from("none"), |c| c.to_string());
+        let attr = |on: bool, name: &str| format!("style:{}{}", if on { "" } else { "no" }, name);
+        let change = match self.change {
+            Change::Set(style) => style.to_string(),
+            Change::Fg(c) => format!("fg:{}", color(c)),
+            Change::Bg(c) => format!("bg:{}", color(c)),
+            Change::Bold(on) => attr(on, "bold"),
+            Change::Italic(on) => attr(on, "italic"),
+            Change::Underline(on) => attr(on, "underline"),
+        };
+        write!(f, "{}:{}", self.slot.name(), change)
+    }
+}
+
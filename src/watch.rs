@@ -0,0 +1,409 @@
+//! This module is related to **watching** files and directories for changes.
+//! Whenever one of the watched paths changes, the screen is cleared and
+//! the search is performed again. On Linux, `inotify` is used to wait for changes;
+//! everywhere else (or if `inotify` cannot be used) the paths are polled.
+
+
+use std::collections::{ HashMap, };
+use std::fs;
+use std::io::{ self, Error, IsTerminal, Write, };
+use std::path::{ Path, };
+use std::thread::{ sleep, };
+use std::time::{ Duration, SystemTime, };
+use crate::{ search, create_rf, glob, };
+use crate::cells::{ Cell, };
+use crate::errors::{ LenzError, };
+use crate::opts::{ Format, Mode, Options, };
+use crate::printer::{ self, Printer, };
+use crate::rf::{ NumberedLines, Shown, };
+use crate::searcher::{ Searcher, };
+use crate::style::{ ANSIStyle, Look, };
+
+
+/// The time between two checks of the watched paths when polling.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+/// Changes usually come in bursts (an editor saving a file),
+/// so after the first one, `lenz` waits this long before searching again.
+const SETTLE_TIME: Duration = Duration::from_millis(100);
+/// The ANSI escape codes to clear the screen and move the cursor to the top.
+const CLEAR: &str = "\x1b[2J\x1b[H";
+
+
+/// The task of this function is to search the files, then wait for a change and search again, forever.
+///
+/// # Arguments
+///
+/// * `opts` - The options entered by the user.
+/// * `searcher` - The compiled search.
+/// * `look` - How the output is styled.
+/// * `out` - Where the results are written.
+/// * `err` - Where the errors of the formats read by other programs are written.
+pub fn watch(opts: &Options, searcher: &Searcher, look: &Look, out: &mut dyn Write, err: &mut dyn Write) -> ! {
+    let mut previous: Option<HashMap<String, NumberedLines>> = None;
+    loop {
+        let files: Vec<String> = expand_paths(&opts.files).into_iter()
+                                                          .filter(|f| !glob::is_excluded(&opts.exclude, f))
+                                                          .collect();
+        // Machine-readable output (or a pipe) only gets the results.
+        if opts.format == Format::Text {
+            if io::stdout().is_terminal() {
+                let _ = write!(out, "{}", CLEAR);
+            }
+            let _ = printer::for_options(opts, look, out, err).notice(&format!(
+                "{}* Watching {} path(s) {}(Ctrl-C to quit){}",
+                look.ansi(ANSIStyle::Bold),
+                opts.files.len(),
+                look.ansi(ANSIStyle::Italic),
+                look.ansi(ANSIStyle::Reset),
+            ));
+        }
+        // Changes made while searching are caught by the next wait.
+        let changes = Changes::start(&opts.files);
+        let searched = match previous.as_mut() {
+            Some(prev) => search_diff(&files, opts, searcher, look, prev, Some(printer::for_options(opts, look, out, err).as_mut())),
+            None if opts.diff => search_first(&files, opts, searcher, look, out, err).map(|first| previous = Some(first)),
+            None => search(&files, opts, searcher, look, printer::for_options(opts, look, out, err).as_mut()).map(|_| ()),
+        };
+        if let Err(e) = searched {
+            let _ = writeln!(err, "{}", e);
+        }
+        changes.wait(&opts.files);
+    }
+}
+
+
+/// The task of this function is to turn the watched paths into the files to be searched.
+/// A directory stands for the regular files directly inside it, in name order.
+///
+/// # Arguments
+///
+/// * `paths` - The paths entered by the user.
+///
+/// # Returns
+///
+/// The paths of the files to be searched.
+pub fn expand_paths(paths: &[String]) -> Vec<String> {
+    let mut files = Vec::new();
+    for path in paths {
+        match fs::read_dir(path) {
+            Ok(entries) => {
+                let mut inner: Vec<String> = entries.filter_map(|e| e.ok())
+                                                    .map(|e| e.path())
+                                                    .filter(|p| p.is_file())
+                                                    .map(|p| p.to_string_lossy().to_string())
+                                                    .collect();
+                inner.sort();
+                files.extend(inner);
+            },
+            Err(_) => files.push(path.to_string()),
+        }
+    }
+    files
+}
+
+
+/// The task of this function is to find the matches that were added and removed between two runs.
+/// Lines are compared by their content, so matches that only moved to another line number
+/// (because lines were inserted above them) are not reported.
+///
+/// # Arguments
+///
+/// * `previous` - The matched lines of the previous run.
+/// * `current` - The matched lines of this run.
+///
+/// # Returns
+///
+/// A tuple of the `added` and `removed` lines.
+pub fn diff_matches(previous: &[(usize, String)], current: &[(usize, String)]) -> (NumberedLines, NumberedLines) {
+    let mut counts: HashMap<&str, isize> = HashMap::new();
+    for (_, line) in previous {
+        *counts.entry(line).or_default() += 1;
+    }
+    let mut added = Vec::new();
+    for (n, line) in current {
+        match counts.get_mut(line.as_str()) {
+            Some(c) if *c > 0 => *c -= 1,
+            _ => added.push((*n, line.to_string())),
+        }
+    }
+    let mut removed = Vec::new();
+    for (n, line) in previous.iter().rev() {
+        if let Some(c) = counts.get_mut(line.as_str()) && *c > 0 {
+            *c -= 1;
+            removed.push((*n, line.to_string()));
+        }
+    }
+    removed.reverse();
+    (added, removed)
+}
+
+
+/// The task of this function is to search the files for the first time with `--diff`,
+/// remembering the matched lines that are displayed for the next run.
+/// The files whose matched lines were not all displayed (like when counting,
+/// or because of a limit) are searched again to remember them.
+///
+/// # Returns
+///
+/// The matched lines of each file that could be searched.
+///
+/// # Errors
+///
+/// If the printer fails to write.
+fn search_first(files: &[String], opts: &Options, searcher: &Searcher, look: &Look, out: &mut dyn Write, err: &mut dyn Write) -> Result<HashMap<String, NumberedLines>, Error> {
+    let mut recorder = Recorder { inner: printer::for_options(opts, look, out, err), lines: HashMap::new() };
+    search(files, opts, searcher, look, &mut recorder)?;
+    let mut previous = recorder.lines;
+    // Counting and listing do not display the matched lines.
+    let partial: Vec<String> = match opts.mode {
+        Mode::Cells | Mode::Page => files.iter().filter(|f| !previous.contains_key(*f)).cloned().collect(),
+        _ => files.to_vec(),
+    };
+    if !partial.is_empty() {
+        let mut rest = HashMap::new();
+        search_diff(&partial, opts, searcher, look, &mut rest, None)?;
+        previous.extend(rest);
+    }
+    Ok(previous)
+}
+
+
+/// A `Printer` that passes everything on to another one, and remembers the matched lines
+/// of each file that was read without a problem and without a limit cutting it short.
+struct Recorder<'a> {
+    inner: Box<dyn Printer + 'a>,
+    lines: HashMap<String, NumberedLines>,
+}
+
+
+impl Printer for Recorder<'_> {
+    fn needs_count(&self) -> bool {
+        self.inner.needs_count()
+    }
+
+    fn begin(&mut self) -> Result<(), Error> {
+        self.inner.begin()
+    }
+
+    fn file_start(&mut self, path: &str, count: Option<usize>) -> Result<(), Error> {
+        self.inner.file_start(path, count)
+    }
+
+    fn matched(&mut self, path: &str, cell: &Cell) -> Result<(), Error> {
+        self.lines.entry(path.to_string()).or_default().push((cell.lineno(), cell.line().to_string()));
+        self.inner.matched(path, cell)
+    }
+
+    fn context(&mut self, path: &str, cell: &Cell) -> Result<(), Error> {
+        self.inner.context(path, cell)
+    }
+
+    fn file_end(&mut self, path: &str, shown: Shown) -> Result<(), Error> {
+        if shown.failed || shown.truncated {
+            self.lines.remove(path);
+        } else {
+            self.lines.entry(path.to_string()).or_default();
+        }
+        self.inner.file_end(path, shown)
+    }
+
+    fn error(&mut self, error: &LenzError) -> Result<(), Error> {
+        self.inner.error(error)
+    }
+
+    fn changed(&mut self, path: &str, cell: &Cell, added: bool) -> Result<(), Error> {
+        self.inner.changed(path, cell, added)
+    }
+
+    fn notice(&mut self, text: &str) -> Result<(), Error> {
+        self.inner.notice(text)
+    }
+
+    fn finish(&mut self, truncated: Option<&str>) -> Result<(), Error> {
+        self.inner.finish(truncated)
+    }
+}
+
+
+/// The task of this function is to search the files and only send to the `printer`
+/// what changed compared to the previous run, which is then replaced.
+/// Without a `printer`, the matches are only remembered for the next run.
+///
+/// # Errors
+///
+/// If the printer fails to write.
+fn search_diff(files: &[String], opts: &Options, searcher: &Searcher, look: &Look, previous: &mut HashMap<String, NumberedLines>, mut printer: Option<&mut dyn Printer>) -> Result<(), Error> {
+    let mut gr: Vec<LenzError> = Vec::new();
+    let mut current = HashMap::new();
+    let mut changed = false;
+    for file in files {
+        let read = create_rf(file, opts, searcher, look).and_then(|rf| {
+            rf.matched_lines().map(|lines| (rf, lines)).map_err(|e| LenzError::read(file, e))
+        });
+        match read {
+            Ok((rf, lines)) => {
+                let prev = previous.get(file).map(|v| v.as_slice()).unwrap_or(&[]);
+                let (added, removed) = diff_matches(prev, &lines);
+                if let Some(printer) = printer.as_mut() && (!added.is_empty() || !removed.is_empty()) {
+                    rf.send_diff(*printer, &lines, &added, &removed)?;
+                    changed = true;
+                }
+                current.insert(file.to_string(), lines);
+            },
+            Err(e) => gr.push(e),
+        }
+    }
+    *previous = current;
+    let Some(printer) = printer else {
+        return Ok(());
+    };
+    if !changed {
+        printer.notice(&format!("\n{}No matches were added or removed.{}", look.ansi(ANSIStyle::Italic), look.ansi(ANSIStyle::Reset)))?;
+    }
+    for e in &gr {
+        printer.error(e)?;
+    }
+    printer.finish(None)
+}
+
+
+/// The watched paths as they were before a search, to wait for them to change afterwards.
+enum Changes {
+    /// The `inotify` watches of the paths, which keep the events that arrive during the search.
+    #[cfg(target_os = "linux")]
+    Inotify(inotify::Watch),
+    /// The `State` of the paths, which is polled.
+    Polling(Vec<(String, State)>),
+}
+
+
+impl Changes {
+    /// The task of this function is to start watching the paths, with `inotify` if it can be used.
+    fn start(paths: &[String]) -> Self {
+        #[cfg(target_os = "linux")]
+        if let Ok(watch) = inotify::Watch::new(paths) {
+            return Changes::Inotify(watch);
+        }
+        Changes::Polling(snapshot(paths))
+    }
+
+    /// The task of this method is to block until one of the watched paths changes,
+    /// counting the changes made since `start`.
+    fn wait(self, paths: &[String]) {
+        let before = match self {
+            #[cfg(target_os = "linux")]
+            Changes::Inotify(watch) => match watch.wait() {
+                Ok(()) => {
+                    sleep(SETTLE_TIME);
+                    return;
+                },
+                Err(_) => snapshot(paths),
+            },
+            Changes::Polling(before) => before,
+        };
+        loop {
+            if snapshot(paths) != before {
+                sleep(SETTLE_TIME);
+                return;
+            }
+            sleep(POLL_INTERVAL);
+        }
+    }
+}
+
+
+/// The state of one path for polling: its modification time and size,
+/// or the same for every entry if it is a directory.
+type State = Option<(Option<SystemTime>, u64)>;
+
+
+/// Takes the `State` of every watched path (and every entry of watched directories).
+fn snapshot(paths: &[String]) -> Vec<(String, State)> {
+    let state = |p: &Path| -> State {
+        fs::metadata(p).ok().map(|m| (m.modified().ok(), m.len()))
+    };
+    let mut states = Vec::new();
+    for path in paths {
+        states.push((path.to_string(), state(Path::new(path))));
+        if let Ok(entries) = fs::read_dir(path) {
+            for entry in entries.filter_map(|e| e.ok()) {
+                let p = entry.path();
+                states.push((p.to_string_lossy().to_string(), state(&p)));
+            }
+        }
+    }
+    states.sort();
+    states
+}
+
+
+/// Waiting for changes with Linux's `inotify`.
+#[cfg(target_os = "linux")]
+mod inotify {
+    use std::ffi::{ CString, };
+    use std::io::{ Error, };
+
+    /// The events that count as a change.
+    const MASK: u32 = libc::IN_MODIFY | libc::IN_ATTRIB | libc::IN_CLOSE_WRITE
+                    | libc::IN_CREATE | libc::IN_DELETE | libc::IN_DELETE_SELF
+                    | libc::IN_MOVED_FROM | libc::IN_MOVED_TO | libc::IN_MOVE_SELF;
+
+    /// An `inotify` instance watching the paths, which is closed when dropped.
+    /// A new one is created for each search, so files replaced by editors
+    /// (written elsewhere and renamed) are watched again next time.
+    pub struct Watch {
+        fd: i32,
+    }
+
+    impl Watch {
+        /// The task of this function is to add a watch for each path.
+        ///
+        /// # Errors
+        ///
+        /// If `inotify` is not available or one of the paths cannot be watched.
+        pub fn new(paths: &[String]) -> Result<Self, Error> {
+            // SAFETY: A plain system call, whose file descriptor is owned by the `Watch`.
+            let fd = unsafe { libc::inotify_init1(libc::IN_CLOEXEC) };
+            if fd < 0 {
+                return Err(Error::last_os_error());
+            }
+            let watch = Watch { fd };
+            // SAFETY: `fd` is a valid `inotify` instance until the `Watch` is dropped.
+            unsafe { add_watches(watch.fd, paths)? };
+            Ok(watch)
+        }
+
+        /// Blocks until an event arrives for one of the paths, unless one already has.
+        ///
+        /// # Errors
+        ///
+        /// If the events cannot be read.
+        pub fn wait(&self) -> Result<(), Error> {
+            let mut buf = [0u8; 4096];
+            // SAFETY: The buffer is valid for its whole length, and `fd` is open.
+            if unsafe { libc::read(self.fd, buf.as_mut_ptr().cast(), buf.len()) } < 0 {
+                return Err(Error::last_os_error());
+            }
+            Ok(())
+        }
+    }
+
+    impl Drop for Watch {
+        fn drop(&mut self) {
+            // SAFETY: `fd` is owned by the `Watch` and is closed only once.
+            unsafe { libc::close(self.fd) };
+        }
+    }
+
+    /// Adds a watch to the `inotify` instance for each path.
+    unsafe fn add_watches(fd: i32, paths: &[String]) -> Result<(), Error> {
+        for path in paths {
+            let c_path = CString::new(path.as_str()).map_err(Error::other)?;
+            // SAFETY: `c_path` is a valid NUL-terminated string for the duration of the call.
+            if unsafe { libc::inotify_add_watch(fd, c_path.as_ptr(), MASK) } < 0 {
+                return Err(Error::last_os_error());
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,232 @@
+//! This module is related to the **display width** of text in a terminal.
+//! Most characters take one column, but East Asian wide characters and most emoji
+//! take two, combining marks take none, and a tab jumps to the next tab stop.
+//! A character joined to the previous one by a zero-width joiner (like in 👩‍💻) takes no column either.
+//! The distance between tab stops is given to a `Cursor` (`--tab-width N`, held by the `Look` of the output).
+//! The width of the terminal itself is found with `terminal_width`.
+
+
+use std::env;
+
+
+/// The default distance between two tab stops.
+pub const TAB_WIDTH: usize = 8;
+/// The zero-width joiner, which glues characters together into a single one (like emoji sequences).
+const ZWJ: char = '\u{200D}';
+
+
+/// Ranges of characters that take **two** columns (East Asian Wide and Fullwidth, and emoji).
+const WIDE: &[(u32, u32)] = &[
+    (0x1100, 0x115F), (0x231A, 0x231B), (0x2329, 0x232A), (0x23E9, 0x23EC),
+    (0x23F0, 0x23F0), (0x23F3, 0x23F3), (0x25FD, 0x25FE), (0x2614, 0x2615),
+    (0x2648, 0x2653), (0x267F, 0x267F), (0x2693, 0x2693), (0x26A1, 0x26A1),
+    (0x26AA, 0x26AB), (0x26BD, 0x26BE), (0x26C4, 0x26C5), (0x26CE, 0x26CE),
+    (0x26D4, 0x26D4), (0x26EA, 0x26EA), (0x26F2, 0x26F3), (0x26F5, 0x26F5),
+    (0x26FA, 0x26FA), (0x26FD, 0x26FD), (0x2705, 0x2705), (0x270A, 0x270B),
+    (0x2728, 0x2728), (0x274C, 0x274C), (0x274E, 0x274E), (0x2753, 0x2755),
+    (0x2757, 0x2757), (0x2795, 0x2797), (0x27B0, 0x27B0), (0x27BF, 0x27BF),
+    (0x2B1B, 0x2B1C), (0x2B50, 0x2B50), (0x2B55, 0x2B55), (0x2E80, 0x303E),
+    (0x3041, 0x33FF), (0x3400, 0x4DBF), (0x4E00, 0x9FFF), (0xA000, 0xA4CF),
+    (0xA960, 0xA97F), (0xAC00, 0xD7A3), (0xF900, 0xFAFF), (0xFE10, 0xFE19),
+    (0xFE30, 0xFE6F), (0xFF00, 0xFF60), (0xFFE0, 0xFFE6), (0x16FE0, 0x16FE4),
+    (0x17000, 0x18CFF), (0x1B000, 0x1B2FF), (0x1F004, 0x1F004), (0x1F0CF, 0x1F0CF),
+    (0x1F18E, 0x1F18E), (0x1F191, 0x1F19A), (0x1F200, 0x1F202), (0x1F210, 0x1F23B),
+    (0x1F240, 0x1F248), (0x1F250, 0x1F251), (0x1F260, 0x1F265), (0x1F300, 0x1F320),
+    (0x1F32D, 0x1F335), (0x1F337, 0x1F37C), (0x1F37E, 0x1F393), (0x1F3A0, 0x1F3CA),
+    (0x1F3CF, 0x1F3D3), (0x1F3E0, 0x1F3F0), (0x1F3F4, 0x1F3F4), (0x1F3F8, 0x1F3FA),
+    (0x1F400, 0x1F43E), (0x1F440, 0x1F440), (0x1F442, 0x1F4FC), (0x1F4FF, 0x1F53D),
+    (0x1F54B, 0x1F54E), (0x1F550, 0x1F567), (0x1F57A, 0x1F57A), (0x1F595, 0x1F596),
+    (0x1F5A4, 0x1F5A4), (0x1F5FB, 0x1F64F), (0x1F680, 0x1F6C5), (0x1F6CC, 0x1F6CC),
+    (0x1F6D0, 0x1F6D2), (0x1F6D5, 0x1F6D7), (0x1F6DC, 0x1F6DF), (0x1F6EB, 0x1F6EC),
+    (0x1F6F4, 0x1F6FC), (0x1F7E0, 0x1F7EB), (0x1F7F0, 0x1F7F0), (0x1F90C, 0x1F93A),
+    (0x1F93C, 0x1F945), (0x1F947, 0x1F9FF), (0x1FA70, 0x1FAFF), (0x20000, 0x2FFFD),
+    (0x30000, 0x3FFFD),
+];
+
+
+/// Ranges of characters that take **no** columns (combining marks, format characters and modifiers).
+const ZERO: &[(u32, u32)] = &[
+    (0x0300, 0x036F), (0x0483, 0x0489), (0x0591, 0x05BD), (0x05BF, 0x05BF),
+    (0x05C1, 0x05C2), (0x05C4, 0x05C5), (0x05C7, 0x05C7), (0x0610, 0x061A),
+    (0x064B, 0x065F), (0x0670, 0x0670), (0x06D6, 0x06DC), (0x06DF, 0x06E4),
+    (0x06E7, 0x06E8), (0x06EA, 0x06ED), (0x0E31, 0x0E31), (0x0E34, 0x0E3A),
+    (0x0E47, 0x0E4E), (0x1AB0, 0x1AFF), (0x1DC0, 0x1DFF), (0x200B, 0x200F),
+    (0x2028, 0x202E), (0x2060, 0x2064), (0x20D0, 0x20FF), (0xFE00, 0xFE0F),
+    (0xFE20, 0xFE2F), (0xFEFF, 0xFEFF), (0x1F3FB, 0x1F3FF), (0xE0000, 0xE007F),
+    (0xE0100, 0xE01EF),
+];
+
+
+/// The task of this function is to find the number of columns a character takes.
+/// Tabs are not handled here, because their width depends on where they are.
+///
+/// # Arguments
+///
+/// * `c` - The character.
+///
+/// # Returns
+///
+/// `0`, `1` or `2`.
+pub fn char_width(c: char) -> usize {
+    let cp = c as u32;
+    if cp < 0x7F {
+        return if cp < 0x20 { 0 } else { 1 };
+    }
+    if cp < 0xA0 || in_table(ZERO, cp) {
+        return 0;
+    }
+    if in_table(WIDE, cp) { 2 } else { 1 }
+}
+
+
+/// The task of this function is to find the number of columns a string takes,
+/// expanding tabs to the default stops (`TAB_WIDTH`) as if the string started at the first column.
+///
+/// # Arguments
+///
+/// * `text` - The string.
+///
+/// # Returns
+///
+/// The display width of the string.
+pub fn str_width(text: &str) -> usize {
+    let mut cursor = Cursor::new(TAB_WIDTH);
+    text.chars().for_each(|c| { cursor.advance(c); });
+    cursor.column()
+}
+
+
+/// The task of this function is to find the spaces that pad a string to a display width.
+///
+/// # Arguments
+///
+/// * `text` - The string, which may have wide characters.
+/// * `width` - The number of columns the string and its padding should take.
+///
+/// # Returns
+///
+/// The spaces, or an empty `String` if the string is already as wide.
+pub fn padding(text: &str, width: usize) -> String {
+    " ".repeat(width.saturating_sub(str_width(text)))
+}
+
+
+/// The task of this function is to find the display column at which a byte index of a line starts.
+///
+/// # Arguments
+///
+/// * `line` - The line.
+/// * `byte` - A byte index in the line. If it falls inside a character, that character is used.
+/// * `tab_width` - The distance between two tab stops.
+///
+/// # Returns
+///
+/// The column, counted from `1`.
+pub fn column_at(line: &str, byte: usize, tab_width: usize) -> usize {
+    let mut b = byte.min(line.len());
+    while !line.is_char_boundary(b) {
+        b -= 1;
+    }
+    let mut cursor = Cursor::new(tab_width);
+    line[..b].chars().for_each(|c| { cursor.advance(c); });
+    cursor.column() + 1
+}
+
+
+/// The task of this function is to find the number of columns of the terminal the output is written to.
+///
+/// # Returns
+///
+/// `COLUMNS` if it is set, otherwise the width of the terminal behind `stdout`,
+/// or `None` if it is not a terminal.
+pub fn terminal_width() -> Option<usize> {
+    if let Some(columns) = env::var("COLUMNS").ok().and_then(|c| c.parse().ok()) && columns > 0 {
+        return Some(columns);
+    }
+    stdout_size().map(|(columns, _)| columns)
+}
+
+
+/// The task of this function is to find the number of rows of the terminal the output is written to.
+///
+/// # Returns
+///
+/// `LINES` if it is set, otherwise the height of the terminal behind `stdout`,
+/// or `None` if it is not a terminal.
+pub fn terminal_height() -> Option<usize> {
+    if let Some(lines) = env::var("LINES").ok().and_then(|l| l.parse().ok()) && lines > 0 {
+        return Some(lines);
+    }
+    stdout_size().map(|(_, rows)| rows)
+}
+
+
+/// A `Cursor` walks along a line character by character, keeping the display column it is at.
+#[derive(Clone, Copy, Debug)]
+pub struct Cursor {
+    column: usize,
+    tab_width: usize,
+    /// Whether the previous character was a zero-width joiner.
+    joined: bool,
+}
+
+
+impl Cursor {
+    /// `Cursor` Constructor.
+    /// The cursor starts at the first column (`0`).
+    pub fn new(tab_width: usize) -> Self {
+        Self { column: 0, tab_width: tab_width.max(1), joined: false }
+    }
+
+    /// `column` attr getter, counted from `0`.
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// The task of this method is to move the cursor past a character.
+    ///
+    /// # Returns
+    ///
+    /// The number of columns the character takes.
+    pub fn advance(&mut self, c: char) -> usize {
+        let width = match c {
+            '\t' => self.tab_width - self.column % self.tab_width,
+            _ if self.joined => 0,
+            c => char_width(c),
+        };
+        self.joined = c == ZWJ;
+        self.column += width;
+        width
+    }
+}
+
+
+/// Binary search in a sorted table of ranges.
+fn in_table(table: &[(u32, u32)], cp: u32) -> bool {
+    table.binary_search_by(|&(lo, hi)| {
+        if hi < cp {
+            std::cmp::Ordering::Less
+        } else if lo > cp {
+            std::cmp::Ordering::Greater
+        } else {
+            std::cmp::Ordering::Equal
+        }
+    }).is_ok()
+}
+
+
+/// Asks the terminal behind `stdout` for its size, as columns and rows.
+#[cfg(target_os = "linux")]
+fn stdout_size() -> Option<(usize, usize)> {
+    // SAFETY: `winsize` is plain data, which `ioctl` fills in when it succeeds.
+    let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+    let ok = unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) } == 0;
+    (ok && size.ws_col > 0 && size.ws_row > 0).then_some((usize::from(size.ws_col), usize::from(size.ws_row)))
+}
+
+
+/// Asks the terminal behind `stdout` for its size, which is not supported on this platform.
+#[cfg(not(target_os = "linux"))]
+fn stdout_size() -> Option<(usize, usize)> {
+    None
+}
@@ -0,0 +1,62 @@
+use lenz::cells::{Cell, Fit, Gutter};
+use lenz::matcher::Matcher;
+use lenz::printer::get_vimgrep_rows;
+use lenz::style::Look;
+
+
+#[test]
+fn test_vimgrep_rows() {
+    let matcher = Matcher::new("lenz", false);
+    let look = Look::plain();
+    let cell = Cell::new(&matcher, &look, String::from("a lenz b lenz"), 4, 0, 1);
+    assert_eq!(
+        get_vimgrep_rows("src/a.rs", &cell),
+        vec!["src/a.rs:4:3:a lenz b lenz", "src/a.rs:4:10:a lenz b lenz"],
+    );
+}
+
+
+#[test]
+fn test_vimgrep_rows_no_match() {
+    let matcher = Matcher::new("lenz", false);
+    let look = Look::plain();
+    let cell = Cell::new(&matcher, &look, String::from("nothing"), 1, 0, 1);
+    assert!(get_vimgrep_rows("a", &cell).is_empty());
+}
+
+
+#[test]
+fn test_to_text_truncated() {
+    let matcher = Matcher::new("lenz", false);
+    let look = Look::plain();
+    let line = format!("{}lenz{}", "a".repeat(50), "b".repeat(50));
+    let cell = Cell::new(&matcher, &look, line, 3, 0, 0);
+    assert_eq!(
+        cell.to_text(None, Gutter::default(), None, Fit::Truncate(10)),
+        "3       [+47 chars]…aaalenzbbb…[+47 chars]",
+    );
+    let cell = Cell::new(&matcher, &look, String::from("a lenz"), 3, 0, 0);
+    assert_eq!(cell.to_text(None, Gutter::default(), None, Fit::Truncate(10)), "3       a lenz");
+}
+
+
+#[test]
+fn test_to_text_wrapped() {
+    let matcher = Matcher::new("lenz", false);
+    let look = Look::plain();
+    let line = format!("{}lenz", "a".repeat(20));
+    let cell = Cell::new(&matcher, &look, line, 3, 0, 0);
+    assert_eq!(
+        cell.to_text(None, Gutter::default(), None, Fit::Wrap(18)),
+        "3       aaaaaaaaaa\n        aaaaaaaaaa\n        lenz",
+    );
+}
+
+
+#[test]
+fn test_vimgrep_rows_case_changing_length() {
+    let matcher = Matcher::new("x", true);
+    let look = Look::plain();
+    let cell = Cell::new(&matcher, &look, String::from("İx and x"), 1, 0, 1);
+    assert_eq!(get_vimgrep_rows("u.txt", &cell), vec!["u.txt:1:3:İx and x", "u.txt:1:9:İx and x"]);
+}
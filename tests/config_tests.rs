@@ -0,0 +1,76 @@
+use lenz::config::{key_of, parse, Value};
+use lenz::opts::{parse_args, parse_args_with_config, Action};
+use lenz::searcher::CaseMode;
+
+
+fn args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|a| a.to_string()).collect()
+}
+
+
+#[test]
+fn test_parse_config() {
+    let text = "# defaults\ncase = \"smart\"\n\nexclude = ['target', \"*.log\"]  # not searched\ncontext = 2\ncolumn = true\nmax_count = 5\n";
+    let settings = parse("lenz.toml", text).unwrap();
+    assert_eq!(settings.len(), 5);
+    assert_eq!(settings[0].key, "case");
+    assert_eq!(settings[0].source(), "lenz.toml:2");
+    assert_eq!(settings[1].value, Value::List(vec![String::from("target"), String::from("*.log")]));
+    assert_eq!(settings[4].key, "max-count");
+    assert_eq!(settings[4].to_args().unwrap(), vec![String::from("--max-count=5")]);
+}
+
+
+#[test]
+fn test_parse_config_errors() {
+    assert_eq!(parse("c", "[search]\n").unwrap_err().kind(), "config");
+    assert!(parse("c", "colour = \"never\"\n").is_err());
+    assert!(parse("c", "column = \"yes\"\n").is_err());
+    assert!(parse("c", "format = \"json\n").is_err());
+    let e = parse("c", "\nexclude = [1]\n").unwrap_err();
+    assert_eq!(e.plain(), "Config file 'c', line 2: The value of 'exclude' is not valid.");
+}
+
+
+#[test]
+fn test_config_merged_with_args() {
+    let settings = parse("c", "case = \"smart\"\ncontext = 3\nexclude = \"*.log\"\n").unwrap();
+    let Ok(Action::Search(opts)) = parse_args_with_config(&args(&["q", "f", "-i", "--exclude", "*.bak"]), &settings) else {
+        panic!("The arguments should be valid.");
+    };
+    assert_eq!(opts.case, CaseMode::Insensitive);
+    assert_eq!(opts.context, 3);
+    assert_eq!(opts.exclude, vec![String::from("*.log"), String::from("*.bak")]);
+    let bad = parse("c", "context = \"many\"\n").unwrap();
+    assert!(parse_args_with_config(&args(&["q", "f"]), &bad).is_err());
+}
+
+
+#[test]
+fn test_print_config() {
+    let settings = parse("c", "case = \"smart\"\n").unwrap();
+    let Ok(Action::PrintConfig(report)) = parse_args_with_config(&args(&["--print-config", "-C", "2"]), &settings) else {
+        panic!("The arguments should be valid.");
+    };
+    assert!(report.contains("case = \"smart\""));
+    assert!(report.contains("# c:1"));
+    assert!(report.lines().any(|l| l.starts_with("context = 2") && l.ends_with("# command line")));
+    assert!(report.lines().any(|l| l.starts_with("format = \"text\"") && l.ends_with("# default")));
+    assert_eq!(key_of("-S"), Some("case"));
+    assert_eq!(key_of("--max-total"), Some("max-total"));
+    assert!(matches!(parse_args(&args(&["--print-config"])), Ok(Action::PrintConfig(_))));
+}
+
+
+#[test]
+fn test_config_theme() {
+    let settings = parse("c", "theme = \"light\"\ncolors = [\"match:bg:blue\", \"path:none\"]\n").unwrap();
+    let Ok(Action::Search(opts)) = parse_args_with_config(&args(&["q", "f", "--colors", "count:fg:red"]), &settings) else {
+        panic!("The arguments should be valid.");
+    };
+    assert_eq!(opts.theme, "light");
+    let colors: Vec<String> = opts.colors.iter().map(|c| c.to_string()).collect();
+    assert_eq!(colors, vec!["match:bg:blue", "path:none", "count:fg:red"]);
+    let bad = parse("c", "theme = \"dark-ish\"\n").unwrap();
+    assert!(parse_args_with_config(&args(&["q", "f"]), &bad).is_err());
+}
@@ -0,0 +1,33 @@
+use lenz::cells::Cell;
+use lenz::csv::{self, Column};
+use lenz::matcher::Matcher;
+use lenz::style::Look;
+
+
+#[test]
+fn test_field() {
+    assert_eq!(csv::field("plain", false), "plain");
+    assert_eq!(csv::field("a,b", false), "\"a,b\"");
+    assert_eq!(csv::field("say \"hi\"", false), "\"say \"\"hi\"\"\"");
+    assert_eq!(csv::field("two\nlines", false), "\"two\nlines\"");
+    assert_eq!(csv::field("a\tb\\c", true), "a\\tb\\\\c");
+}
+
+
+#[test]
+fn test_parse_list() {
+    assert_eq!(Column::parse_list("path, line,match"), Some(vec![Column::Path, Column::Line, Column::Match]));
+    assert_eq!(Column::parse_list("path,nope"), None);
+    assert_eq!(csv::header(&[Column::ByteOffset, Column::Query], true), "offset\tquery");
+}
+
+
+#[test]
+fn test_records() {
+    let matcher = Matcher::new("b", false);
+    let look = Look::plain();
+    let cell = Cell::new(&matcher, &look, String::from("a,b"), 7, 100, 1);
+    let columns = [Column::Line, Column::ByteOffset, Column::Match, Column::Text];
+    assert_eq!(csv::records("x", &cell, &columns, "b", false), vec!["7,102,b,\"a,b\""]);
+    assert_eq!(csv::records("x", &cell, &[Column::Query], "b", true), vec!["b"]);
+}
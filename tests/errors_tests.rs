@@ -0,0 +1,55 @@
+use std::error::Error as _;
+use std::io::{Error, ErrorKind};
+use lenz::errors::LenzError;
+use lenz::opts::get_color_choice;
+use lenz::style::{ColorChoice, Look};
+
+
+#[test]
+fn test_error_kinds() {
+    let e = LenzError::read("a.txt", Error::from(ErrorKind::NotFound));
+    assert_eq!(e.kind(), "not_found");
+    assert!(e.source().is_none());
+    let e = LenzError::read("a.txt", Error::from(ErrorKind::PermissionDenied));
+    assert_eq!(e.kind(), "permission_denied");
+    assert_eq!(e.path(), Some("a.txt"));
+    assert!(e.source().is_some());
+    assert_eq!(LenzError::NotAFile { path: String::from("src") }.kind(), "not_a_file");
+}
+
+
+#[test]
+fn test_error_renderers() {
+    let e = LenzError::InvalidValue { option: String::from("--color"), value: String::from("red") };
+    assert_eq!(e.plain(), "The value of '--color' is not valid: 'red'.");
+    assert_eq!(e.to_string(), e.plain());
+    assert_eq!(
+        LenzError::NotFound { path: String::from("a\"b") }.to_json(),
+        r#"{"type":"error","kind":"not_found","path":"a\"b","message":"File 'a\"b' does not exist."}"#,
+    );
+    assert_eq!(
+        LenzError::Usage(String::from("oops")).to_json(),
+        r#"{"type":"error","kind":"usage","path":null,"message":"oops"}"#,
+    );
+}
+
+
+#[test]
+fn test_error_write_to() {
+    let mut out = Vec::new();
+    LenzError::InvalidOption { option: String::from("-z") }.write_to(&mut out, &Look::plain()).unwrap();
+    let text = String::from_utf8(out).unwrap();
+    assert!(text.ends_with("Error: This option is not valid: '-z'.\n"));
+    assert!(!text.contains('\x1b'));
+}
+
+
+#[test]
+fn test_stderr_look() {
+    let args = |a: &[&str]| a.iter().map(|s| s.to_string()).collect::<Vec<String>>();
+    assert_eq!(get_color_choice(&args(&["q", "f", "--color", "never", "-z"])), ColorChoice::Never);
+    assert_eq!(get_color_choice(&args(&["q", "--color=always", "--color=nope"])), ColorChoice::Always);
+    assert_eq!(get_color_choice(&args(&["q", "--", "--color=never"])), ColorChoice::Auto);
+    assert!(!Look::for_stderr(ColorChoice::Never).colors);
+    assert!(Look::for_stderr(ColorChoice::Always).colors);
+}
@@ -0,0 +1,92 @@
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use lenz::follow::{FileEvent, Follower};
+use lenz::opts::{parse_args, Action};
+use lenz::rf::ReadableFile;
+use lenz::searcher::SearcherBuilder;
+use lenz::style::Look;
+
+
+fn temp_path(name: &str) -> String {
+    let p = env::temp_dir().join(format!("lenz-follow-{}-{}", std::process::id(), name));
+    p.to_string_lossy().to_string()
+}
+
+
+fn append(path: &str, text: &str) {
+    let mut f = OpenOptions::new().append(true).create(true).open(path).unwrap();
+    f.write_all(text.as_bytes()).unwrap();
+}
+
+
+#[test]
+fn test_follow_appended_lines() {
+    let path = temp_path("append");
+    fs::write(&path, "one\ntwo\n").unwrap();
+    let mut follower = Follower::new(&path, None).unwrap();
+    assert_eq!(follower.poll().unwrap(), (FileEvent::Unchanged, vec![]));
+    append(&path, "three\nfour");
+    let e = vec![(3, 8, String::from("three"))];
+    assert_eq!(follower.poll().unwrap(), (FileEvent::Unchanged, e));
+    append(&path, " and more\n");
+    let e = vec![(4, 14, String::from("four and more"))];
+    assert_eq!(follower.poll().unwrap(), (FileEvent::Unchanged, e));
+    fs::remove_file(&path).unwrap();
+}
+
+
+#[test]
+fn test_follow_from_the_search() {
+    let path = temp_path("search");
+    let args = vec![String::from("t"), path.clone()];
+    let Ok(Action::Search(opts)) = parse_args(&args) else { panic!("The arguments should be valid.") };
+    let searcher = SearcherBuilder::new("t").build();
+    let look = Look::plain();
+    for mmap in [false, true] {
+        fs::write(&path, "one\ntwo\nthr").unwrap();
+        let rf = ReadableFile::new(path.clone(), &searcher, &opts, &look, mmap);
+        assert_eq!(rf.reached(), None);
+        rf.matched_lines().unwrap();
+        assert_eq!(rf.reached(), Some((2, 8)));
+        // What is appended between the search and the follower is still reported.
+        append(&path, "ee\nfour\n");
+        let mut follower = Follower::new(&path, rf.reached()).unwrap();
+        let e = vec![(3, 8, String::from("three")), (4, 14, String::from("four"))];
+        assert_eq!(follower.poll().unwrap(), (FileEvent::Unchanged, e));
+    }
+    fs::write(&path, "one\ntwo\nthr").unwrap();
+    let mut follower = Follower::new(&path, None).unwrap();
+    append(&path, "ee\n");
+    assert_eq!(follower.poll().unwrap(), (FileEvent::Unchanged, vec![(3, 8, String::from("three"))]));
+    fs::remove_file(&path).unwrap();
+}
+
+
+#[test]
+fn test_follow_truncated_file() {
+    let path = temp_path("truncate");
+    fs::write(&path, "one\ntwo\nthree\n").unwrap();
+    let mut follower = Follower::new(&path, None).unwrap();
+    fs::write(&path, "new\n").unwrap();
+    let e = vec![(1, 0, String::from("new"))];
+    assert_eq!(follower.poll().unwrap(), (FileEvent::Truncated, e));
+    fs::remove_file(&path).unwrap();
+}
+
+
+#[cfg(unix)]
+#[test]
+fn test_follow_rotated_file() {
+    let path = temp_path("rotate");
+    let old = format!("{}.1", path);
+    fs::write(&path, "one\n").unwrap();
+    let mut follower = Follower::new(&path, None).unwrap();
+    append(&path, "two\n");
+    fs::rename(&path, &old).unwrap();
+    fs::write(&path, "fresh\n").unwrap();
+    let e = vec![(2, 4, String::from("two")), (1, 0, String::from("fresh"))];
+    assert_eq!(follower.poll().unwrap(), (FileEvent::Rotated, e));
+    fs::remove_file(&path).unwrap();
+    fs::remove_file(&old).unwrap();
+}
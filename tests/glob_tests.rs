@@ -0,0 +1,23 @@
+use lenz::glob::{is_excluded, matches};
+
+
+#[test]
+fn test_matches() {
+    assert!(matches("*.rs", "main.rs"));
+    assert!(!matches("*.rs", "src/main.rs"));
+    assert!(matches("src/*.r?", "src/main.rs"));
+    assert!(matches("src/**/*.rs", "src/main.rs"));
+    assert!(matches("src/**/*.rs", "src/a/b/main.rs"));
+    assert!(!matches("src/**/*.rs", "tests/main.rs"));
+}
+
+
+#[test]
+fn test_is_excluded() {
+    let patterns = vec![String::from("target"), String::from("*.log"), String::from("./docs/*.md")];
+    assert!(is_excluded(&patterns, "target/debug/out.txt"));
+    assert!(is_excluded(&patterns, "./logs/app.log"));
+    assert!(is_excluded(&patterns, "docs/intro.md"));
+    assert!(!is_excluded(&patterns, "docs/api/intro.md"));
+    assert!(!is_excluded(&patterns, "src/main.rs"));
+}
@@ -0,0 +1,50 @@
+use lenz::cells::{Cell, Gutter};
+use lenz::html;
+use lenz::matcher::Matcher;
+use lenz::style::{ANSIStyle, Look};
+use lenz::theme::{Slot, Theme};
+
+
+#[test]
+fn test_escape() {
+    assert_eq!(html::escape("<a href=\"x\">&'</a>"), "&lt;a href=&quot;x&quot;&gt;&amp;&#39;&lt;/a&gt;");
+}
+
+
+#[test]
+fn test_from_ansi() {
+    let text = format!(
+        "File {}{}'<x>'{} does not exist.",
+        ANSIStyle::Italic.as_str(),
+        ANSIStyle::FGWhite.as_str(),
+        ANSIStyle::Reset.as_str(),
+    );
+    assert_eq!(
+        html::from_ansi(&text),
+        "File <span class=\"italic\"><span class=\"fg-white\">&#39;&lt;x&gt;&#39;</span></span> does not exist.",
+    );
+    assert_eq!(html::from_ansi(&format!("{}open", ANSIStyle::Bold.as_str())), "<span class=\"bold\">open</span>");
+}
+
+
+#[test]
+fn test_highlight() {
+    let matcher = Matcher::new("b", false);
+    assert_eq!(html::highlight(&Theme::DEFAULT, &matcher, "a<b"), "a&lt;<span class=\"bg-red\">b</span>");
+    let look = Look::plain();
+    let cell = Cell::new(&matcher, &look, String::from("a<b"), 4, 0, 1);
+    assert_eq!(
+        html::cell_row(&Theme::DEFAULT, &cell, false, Gutter::default()),
+        html::row(&Theme::DEFAULT, "4", Slot::Lineno, "a&lt;<span class=\"bg-red\">b</span>"),
+    );
+}
+
+
+#[test]
+fn test_from_ansi_combined() {
+    assert_eq!(
+        html::from_ansi("\x1b[1;38;5;208;48;2;0;0;0mx\x1b[0m"),
+        "<span class=\"bold\" style=\"color: #ff8700; background: #000000;\">x</span>",
+    );
+    assert_eq!(html::from_ansi("\x1b[3;37mx\x1b[0m"), "<span class=\"italic fg-white\">x</span>");
+}
@@ -0,0 +1,41 @@
+use lenz::hyperlink::{self, PathCache};
+
+
+#[test]
+fn test_parse_format() {
+    assert_eq!(hyperlink::parse_format("vscode").unwrap(), "vscode://file{path}:{line}:{col}");
+    assert_eq!(hyperlink::parse_format("none").unwrap(), "");
+    assert_eq!(hyperlink::parse_format("subl://open?url=file://{path}&line={line}").unwrap(), "subl://open?url=file://{path}&line={line}");
+    for bad in ["file://{host}", "{path}", "x://{path}:{row}", "x://{path"] {
+        assert!(hyperlink::parse_format(bad).is_none(), "{}", bad);
+    }
+}
+
+
+#[test]
+fn test_fill_and_link() {
+    let url = hyperlink::fill("vscode://file{path}:{line}:{col}", "/tmp/my notes/ünï.txt", 12, 5);
+    assert_eq!(url, "vscode://file/tmp/my%20notes/%C3%BCn%C3%AF.txt:12:5");
+    assert_eq!(hyperlink::link("x://y", "12"), "\x1b]8;;x://y\x1b\\12\x1b]8;;\x1b\\");
+}
+
+
+#[test]
+fn test_file_and_line() {
+    let paths = PathCache::default();
+    assert_eq!(hyperlink::file("", &paths, "/tmp/a.txt", "a"), "a");
+    assert_eq!(hyperlink::file("file://{path}", &paths, "/tmp/a.txt", "a"), hyperlink::link("file:///tmp/a.txt", "a"));
+    // Without a `{line}`, line numbers are not linked.
+    assert_eq!(hyperlink::line("file://{path}", &paths, "/tmp/a.txt", 3, 1, "3"), "3");
+    assert_eq!(hyperlink::line("ed://{path}#{line}", &paths, "/tmp/a.txt", 3, 1, "3"), hyperlink::link("ed:///tmp/a.txt#3", "3"));
+}
+
+
+#[test]
+fn test_path_cache() {
+    let (a, b) = (PathCache::default(), PathCache::default());
+    let dir = std::env::current_dir().unwrap();
+    assert_eq!(a.absolute("no-such-file.txt"), dir.join("no-such-file.txt").to_string_lossy());
+    assert_eq!(a.absolute("no-such-file.txt"), b.absolute("no-such-file.txt"));
+    assert_eq!(a.absolute("/tmp/other.txt"), "/tmp/other.txt");
+}
@@ -0,0 +1,35 @@
+use lenz::cells::Cell;
+use lenz::json;
+use lenz::matcher::Matcher;
+use lenz::style::Look;
+
+
+#[test]
+fn test_string() {
+    assert_eq!(json::string("lenz"), "\"lenz\"");
+    assert_eq!(json::string("a \"b\" \\ c"), "\"a \\\"b\\\" \\\\ c\"");
+    assert_eq!(json::string("\t\n\u{1}"), "\"\\t\\n\\u0001\"");
+}
+
+
+#[test]
+fn test_line() {
+    let matcher = Matcher::new("lenz", false);
+    let look = Look::plain();
+    let cell = Cell::new(&matcher, &look, String::from("a lenz"), 3, 10, 1);
+    assert_eq!(
+        json::line("f.txt", &cell),
+        "{\"type\":\"match\",\"path\":\"f.txt\",\"line_number\":3,\"byte_offset\":10,\"line\":\"a lenz\",\
+\"submatches\":[{\"text\":\"lenz\",\"start\":2,\"end\":6,\"column\":3}]}",
+    );
+}
+
+
+#[test]
+fn test_matches() {
+    let matcher = Matcher::new("ab", false);
+    let look = Look::plain();
+    let cell = Cell::new(&matcher, &look, String::from("ab-ab"), 1, 0, 1);
+    assert_eq!(json::matches("f", &cell).len(), 2);
+    assert!(json::matches("f", &cell)[1].contains("\"column\":4,\"byte_offset\":3"));
+}
@@ -0,0 +1,62 @@
+use std::process::ExitCode;
+use lenz::{run_with, Outcome};
+
+
+fn temp_file(name: &str, contents: &str) -> String {
+    let path = std::env::temp_dir().join(format!("lenz_lib_{}_{}", std::process::id(), name));
+    std::fs::write(&path, contents).unwrap();
+    path.to_string_lossy().to_string()
+}
+
+
+fn run(args: &[&str]) -> (ExitCode, String, String) {
+    let (mut out, mut err) = (Vec::new(), Vec::new());
+    let code = run_with(args.iter().map(|a| a.to_string()).collect(), &mut out, &mut err);
+    (code, String::from_utf8(out).unwrap(), String::from_utf8(err).unwrap())
+}
+
+
+fn exit_code(args: &[&str]) -> ExitCode {
+    run(args).0
+}
+
+
+#[test]
+fn test_outcome_exit_code() {
+    assert_eq!(Outcome { matched: true, failed: false }.exit_code(), ExitCode::SUCCESS);
+    assert_eq!(Outcome { matched: false, failed: false }.exit_code(), ExitCode::FAILURE);
+    assert_eq!(Outcome { matched: true, failed: true }.exit_code(), ExitCode::from(2));
+}
+
+
+#[test]
+fn test_run_exit_code() {
+    let path = temp_file("exit_code", "one\ntwo lenz\n");
+    let missing = format!("{}.missing", path);
+    assert_eq!(exit_code(&["lenz", &path, "-l"]), ExitCode::SUCCESS);
+    assert_eq!(exit_code(&["four", &path, "-l"]), ExitCode::FAILURE);
+    assert_eq!(exit_code(&["four", &path, "-L"]), ExitCode::SUCCESS);
+    assert_eq!(exit_code(&["lenz", &path, &missing, "-l"]), ExitCode::from(2));
+    assert_eq!(exit_code(&["lenz", &path, "--no-such-option"]), ExitCode::from(2));
+    let report = format!("{}.missing/report.html", path);
+    assert_eq!(exit_code(&["lenz", &path, "--html", &report]), ExitCode::from(2));
+    std::fs::remove_file(&path).unwrap();
+}
+
+
+#[test]
+fn test_run_with_writers() {
+    let path = temp_file("writers", "one\ntwo lenz\n");
+    let (code, out, err) = run(&["lenz", &path, "--color", "never"]);
+    assert_eq!(code, ExitCode::SUCCESS);
+    assert!(out.contains(" 2       two lenz\n"), "{}", out);
+    assert!(err.is_empty());
+    let (code, out, err) = run(&["lenz", &path, "--format", "json", "--no-such-option"]);
+    assert_eq!(code, ExitCode::from(2));
+    assert!(out.is_empty());
+    assert!(err.contains("no-such-option"), "{}", err);
+    let (code, _, err) = run(&["lenz", &path, "--watch", "--diff", "--format", "json"]);
+    assert_eq!(code, ExitCode::from(2));
+    assert!(err.contains("can only be used with the text format"), "{}", err);
+    std::fs::remove_file(&path).unwrap();
+}
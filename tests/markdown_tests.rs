@@ -0,0 +1,31 @@
+use lenz::cells::{Cell, Gutter};
+use lenz::markdown;
+use lenz::matcher::Matcher;
+use lenz::style::Look;
+
+
+#[test]
+fn test_escape() {
+    assert_eq!(markdown::escape("a|b_c*"), "a\\|b\\_c\\*");
+    assert_eq!(markdown::heading("x.md", 1), "## x.md (1 match)");
+    assert_eq!(markdown::row("a|b", 2), "| a\\|b | 2 |");
+}
+
+
+#[test]
+fn test_fenced() {
+    let lines = vec![String::from("1:       plain")];
+    assert_eq!(markdown::fenced(&lines), "```\n1:       plain\n```");
+    let lines = vec![String::from("1:       a ```` b")];
+    assert!(markdown::fenced(&lines).starts_with("`````\n"));
+}
+
+
+#[test]
+fn test_line() {
+    let matcher = Matcher::new("lenz", false);
+    let look = Look::plain();
+    let gutter = Gutter::default();
+    assert_eq!(markdown::line(&Cell::new(&matcher, &look, String::from("a lenz"), 12, 0, 1), gutter), "12:      a lenz");
+    assert_eq!(markdown::line(&Cell::new(&matcher, &look, String::from("other"), 13, 0, 1), gutter), "13-      other");
+}
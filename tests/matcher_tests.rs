@@ -0,0 +1,71 @@
+use lenz::matcher::Matcher;
+
+
+#[test]
+fn test_matcher_case_sensitivity() {
+    let matcher = Matcher::new("lenz", false);
+    let o: Vec<(usize, usize)> = matcher.find_iter("lenz LENZ lenzlenz").collect();
+    assert_eq!(o, vec![(0, 4), (10, 4), (14, 4)]);
+    assert_eq!(matcher.count("no match here"), None);
+}
+
+
+#[test]
+fn test_matcher_case_insensitivity_ascii() {
+    let matcher = Matcher::new("LeNz", true);
+    let o: Vec<(usize, usize)> = matcher.find_iter("lenz LENZ xlEnZx").collect();
+    assert_eq!(o, vec![(0, 4), (5, 4), (11, 4)]);
+}
+
+
+#[test]
+fn test_matcher_case_insensitivity_unicode() {
+    let matcher = Matcher::new("ÖL", true);
+    assert_eq!(matcher.count("öl und ÖL"), Some(2));
+    assert!(matcher.is_match("Heizöl"));
+}
+
+
+#[test]
+fn test_matcher_non_overlapping() {
+    let matcher = Matcher::new("aa", true);
+    assert_eq!(matcher.count("AAAAA"), Some(2));
+}
+
+
+#[test]
+fn test_matcher_agrees_with_count_query() {
+    let text = "Just for testing ... lEnz is cool. YES! lenZ is super cool.";
+    for ignore_case in [false, true] {
+        let matcher = Matcher::new("lenz", ignore_case);
+        assert_eq!(matcher.count(text), lenz::count_query("lenz", text, ignore_case));
+        assert_eq!(
+            Some(matcher.find_iter(text).collect::<Vec<_>>()),
+            lenz::find_all("lenz", text, ignore_case).or(Some(vec![])),
+        );
+    }
+}
+
+
+#[test]
+fn test_matcher_word() {
+    let matcher = Matcher::with_word("lenz", false, true);
+    let o: Vec<(usize, usize)> = matcher.find_iter("lenz lenzlenz (lenz) lenz_").collect();
+    assert_eq!(o, vec![(0, 4), (15, 4)]);
+    let matcher = Matcher::with_word("a a", false, true);
+    let o: Vec<(usize, usize)> = matcher.find_iter("xa a a").collect();
+    assert_eq!(o, vec![(3, 3)]);
+}
+
+
+#[test]
+fn test_matcher_offsets_of_the_original_text() {
+    // `İ` is 2 bytes long, but its lowercase `i̇` is 3 bytes long.
+    let matcher = Matcher::new("x", true);
+    let o: Vec<(usize, usize)> = matcher.find_iter("İx and x").collect();
+    assert_eq!(o, vec![(2, 1), (8, 1)]);
+    let matcher = Matcher::new("İX", true);
+    let o: Vec<(usize, usize)> = matcher.find_iter("aİx").collect();
+    assert_eq!(o, vec![(1, 3)]);
+    assert_eq!(&"aİx"[1..4], "İx");
+}
@@ -0,0 +1,54 @@
+use lenz::matcher::Matcher;
+use lenz::mmap::{count, matched_lines, lines};
+
+
+#[test]
+fn test_mmap_lines_like_buf_read() {
+    let hay = b"first\r\nsecond\n\nlast";
+    let o: Vec<(usize, &[u8])> = lines(hay).collect();
+    let e: Vec<(usize, &[u8])> = vec![
+        (1, b"first"),
+        (2, b"second"),
+        (3, b""),
+        (4, b"last"),
+    ];
+    assert_eq!(o, e);
+}
+
+
+#[test]
+fn test_mmap_lines_empty() {
+    assert_eq!(lines(b"").count(), 0);
+    assert_eq!(lines(b"\n").count(), 1);
+}
+
+
+#[test]
+fn test_mmap_count_case_sensitivity() {
+    let hay = b"lenz is cool.\nLENZ\nlenz lenz\n";
+    assert_eq!(count(hay, &Matcher::new("lenz", false)), 3);
+    assert_eq!(count(hay, &Matcher::new("lenz", true)), 4);
+}
+
+
+#[test]
+fn test_mmap_matched_lines_case_sensitivity() {
+    let hay = b"one lenz\ntwo\nthree lenz lenz\r\nfour\nlenz";
+    let matcher = Matcher::new("lenz", false);
+    let o: Vec<(usize, &[u8])> = matched_lines(hay, &matcher).collect();
+    let e: Vec<(usize, &[u8])> = vec![
+        (1, b"one lenz"),
+        (3, b"three lenz lenz"),
+        (5, b"lenz"),
+    ];
+    assert_eq!(o, e);
+}
+
+
+#[test]
+fn test_mmap_matched_lines_case_insensitivity() {
+    let hay = b"one LENZ\ntwo\nthree lEnz\n";
+    let matcher = Matcher::new("lenz", true);
+    let o: Vec<usize> = matched_lines(hay, &matcher).map(|(n, _)| n).collect();
+    assert_eq!(o, vec![1, 3]);
+}
@@ -0,0 +1,162 @@
+use std::io::{Cursor};
+use lenz::cells::{Gutter};
+use lenz::pager::{Flow, Key, Pager};
+use lenz::range::{Selection};
+use lenz::searcher::{CaseMode, SearcherBuilder};
+use lenz::style::{Look};
+
+
+fn get_lines(count: usize) -> Cursor<Vec<u8>> {
+    let text: String = (1..=count)
+        .map(|n| if n % 10 == 0 { format!("line {} lenz\n", n) } else { format!("line {}\n", n) })
+        .collect();
+    Cursor::new(text.into_bytes())
+}
+
+
+fn get_pager(query: &str) -> Pager<Cursor<Vec<u8>>> {
+    let searcher = SearcherBuilder::new(query).build();
+    let mut pager = Pager::new("file.txt", get_lines(100), (0, 0), &searcher, CaseMode::Sensitive, Gutter::default(), Look::plain());
+    pager.resize(21);
+    pager
+}
+
+
+#[test]
+fn test_key_parse() {
+    assert_eq!(Key::parse(b"n"), Key::Char('n'));
+    assert_eq!(Key::parse("é".as_bytes()), Key::Char('é'));
+    assert_eq!(Key::parse(b"\x1b"), Key::Esc);
+    assert_eq!(Key::parse(b"\x1b[A"), Key::Up);
+    assert_eq!(Key::parse(b"\x1b[6~"), Key::PageDown);
+    assert_eq!(Key::parse(b"\r"), Key::Enter);
+    assert_eq!(Key::parse(b"\x03"), Key::Interrupt);
+    assert_eq!(Key::parse(b"\x1b[99~"), Key::Unknown);
+}
+
+
+#[test]
+fn test_pager_scroll() {
+    let mut pager = get_pager("lenz");
+    pager.handle(Key::Char('j'));
+    assert_eq!(pager.top(), 1);
+    pager.handle(Key::PageDown);
+    assert_eq!(pager.top(), 21);
+    pager.handle(Key::Char('G'));
+    assert_eq!(pager.top(), 80);
+    pager.handle(Key::Down);
+    assert_eq!(pager.top(), 80);
+    pager.handle(Key::Char('g'));
+    assert_eq!(pager.top(), 0);
+    assert_eq!(pager.handle(Key::Char('q')), Flow::NextFile);
+    assert_eq!(pager.handle(Key::Interrupt), Flow::Quit);
+}
+
+
+#[test]
+fn test_pager_matches() {
+    let mut pager = get_pager("lenz");
+    assert_eq!(pager.match_count(), 10);
+    assert!(pager.status().contains("10 matches"));
+    pager.handle(Key::Char('n'));
+    assert_eq!(pager.current(), Some(0));
+    assert!(pager.status().contains("match 1/10"));
+    pager.handle(Key::Char('n'));
+    pager.handle(Key::Char('n'));
+    assert_eq!(pager.current(), Some(2));
+    // Line 30 is not on the first screen of 20 lines.
+    assert_eq!(pager.top(), 23);
+    pager.handle(Key::Char('N'));
+    pager.handle(Key::Char('N'));
+    pager.handle(Key::Char('N'));
+    assert_eq!(pager.current(), Some(9));
+    assert_eq!(pager.top(), 80);
+}
+
+
+#[test]
+fn test_pager_query() {
+    let mut pager = get_pager("lenz");
+    for key in [Key::Char('/'), Key::Char('l'), Key::Char('x'), Key::Backspace, Key::Char('i')] {
+        pager.handle(key);
+    }
+    assert_eq!(pager.status(), "/li");
+    pager.handle(Key::Char('n'));
+    pager.handle(Key::Esc);
+    assert_eq!(pager.query(), "lenz");
+    for key in [Key::Char('/'), Key::Char('9'), Key::Char('9'), Key::Enter] {
+        pager.handle(key);
+    }
+    assert_eq!(pager.query(), "99");
+    assert_eq!(pager.match_count(), 1);
+    assert_eq!(pager.current(), Some(0));
+    assert!(pager.status().contains("match 1/1"));
+}
+
+
+#[test]
+fn test_pager_render() {
+    let mut pager = get_pager("lenz");
+    pager.handle(Key::Char('n'));
+    let frame = pager.render(60);
+    assert!(frame.starts_with("\x1b[H"));
+    assert_eq!(frame.matches("\r\n").count(), 20);
+    // The current match is marked in its own column, before the line number.
+    assert!(frame.contains("\r\n>10 "));
+    assert!(frame.contains("\r\n 11 "));
+    assert!(frame.contains("file.txt  match 1/10  lines 1-20/100"));
+    // The status bar is cut to the screen, or filled up to it.
+    assert!(!frame.contains("q quit"));
+    assert!(pager.render(200).ends_with(' '));
+}
+
+
+#[test]
+fn test_pager_lazy() {
+    let searcher = SearcherBuilder::new("lenz").build();
+    let mut pager = Pager::new("big.txt", get_lines(100_000), (0, 0), &searcher, CaseMode::Sensitive, Gutter::default(), Look::plain());
+    pager.resize(21);
+    pager.render(80);
+    // Only the first window of lines is indexed to draw the first screen.
+    assert!(pager.line_count() < 100_000);
+    assert!(!pager.is_done());
+    assert!(pager.status().contains("lines 1-20/4096+"));
+    for key in [Key::Char('/'), Key::Char('4'), Key::Char('9'), Key::Char('9'), Key::Char('9'), Key::Char('0'), Key::Enter] {
+        pager.handle(key);
+    }
+    // The next match is found by indexing as far as it, and not further.
+    assert_eq!(pager.current(), Some(0));
+    assert!(pager.render(80).contains(">49990 "));
+    assert!(pager.status().contains("match 1/1+"));
+    while !pager.is_done() {
+        pager.step();
+    }
+    assert_eq!(pager.line_count(), 100_000);
+    assert!(pager.status().contains("match 1/1  "));
+}
+
+
+#[test]
+fn test_pager_selection() {
+    let mut reader = Cursor::new(b"a\nb lenz\nc\nd lenz\n".to_vec());
+    let selection = Selection::parse_lines("2:3").unwrap();
+    let start = selection.skip(&mut reader).unwrap();
+    let searcher = SearcherBuilder::new("lenz").selection(Some(selection)).build();
+    let mut pager = Pager::new("file.txt", reader, start, &searcher, CaseMode::Sensitive, Gutter { column: false, byte_offset: true }, Look::plain());
+    let frame = pager.render(80);
+    assert!(frame.starts_with("\x1b[H 2:4 "));
+    assert!(frame.contains("b lenz\x1b[K\r\n 3:9 "));
+    assert!(!frame.contains("d lenz"));
+    assert!(pager.status().contains("1 match  lines 1-2/2"));
+}
+
+
+#[test]
+fn test_pager_max_count() {
+    let searcher = SearcherBuilder::new("lenz").max_count(Some(2)).build();
+    let mut pager = Pager::new("file.txt", get_lines(100), (0, 0), &searcher, CaseMode::Sensitive, Gutter::default(), Look::plain());
+    pager.handle(Key::Char('G'));
+    // The lines stop right before the third matched line.
+    assert_eq!(pager.line_count(), 29);
+    assert_eq!(pager.match_count(), 2);
+}
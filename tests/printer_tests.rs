@@ -0,0 +1,93 @@
+use lenz::cells::{Cell, Fit, Gutter};
+use lenz::errors::LenzError;
+use lenz::matcher::Matcher;
+use lenz::printer::{List, Plain, Printer, Standard};
+use lenz::rf::Shown;
+use lenz::style::Look;
+use lenz::theme::Theme;
+
+
+#[test]
+fn test_standard() {
+    let matcher = Matcher::new("lenz", false);
+    let look = Look::plain();
+    let mut out = Vec::new();
+    let mut printer = Standard::new(&mut out, Look::plain(), Gutter::default(), false, false, Fit::Full);
+    printer.file_start("a.txt", Some(1)).unwrap();
+    printer.matched("a.txt", &Cell::new(&matcher, &look, String::from("a lenz"), 3, 0, 1)).unwrap();
+    printer.error(&LenzError::NotFound { path: String::from("b") }).unwrap();
+    printer.finish(None).unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "\n- Filename: 'a.txt' [1]\n\n 3       a lenz\n\n* Errors Report: \n└──[1] File 'b' does not exist.\n",
+    );
+}
+
+
+#[test]
+fn test_standard_looks() {
+    let matcher = Matcher::new("lenz", false);
+    let (plain, light) = (Look::plain(), Look { theme: Theme::LIGHT, ..Look::default() });
+    let (mut a, mut b) = (Vec::new(), Vec::new());
+    let mut printers = [
+        Standard::new(&mut a, plain.clone(), Gutter::default(), false, false, Fit::Full),
+        Standard::new(&mut b, light.clone(), Gutter::default(), false, false, Fit::Full),
+    ];
+    for (printer, look) in printers.iter_mut().zip([&plain, &light]) {
+        printer.matched("a.txt", &Cell::new(&matcher, look, String::from("a lenz"), 3, 0, 1)).unwrap();
+    }
+    assert_eq!(a, b" 3       a lenz\n");
+    assert_eq!(String::from_utf8(b).unwrap(), " \x1b[34m3\x1b[0m       a \x1b[30;43mlenz\x1b[0m\n");
+}
+
+
+#[test]
+fn test_plain_errors_go_to_err() {
+    let matcher = Matcher::new("x", false);
+    let look = Look::plain();
+    let (mut out, mut err) = (Vec::new(), Vec::new());
+    let mut printer = Plain::new(&mut out, &mut err);
+    printer.matched("f", &Cell::new(&matcher, &look, String::from("x"), 1, 0, 1)).unwrap();
+    printer.error(&LenzError::Usage(String::from("oops"))).unwrap();
+    printer.notice("* File 'f' was truncated.").unwrap();
+    assert_eq!(out, b"f:1:1:x\n");
+    assert_eq!(err, b"oops\n* File 'f' was truncated.\n");
+}
+
+
+#[test]
+fn test_standard_reports_new_errors() {
+    let mut out = Vec::new();
+    let mut printer = Standard::new(&mut out, Look::plain(), Gutter::default(), false, false, Fit::Full);
+    printer.notice("* Watching").unwrap();
+    printer.error(&LenzError::NotFound { path: String::from("a") }).unwrap();
+    printer.finish(None).unwrap();
+    printer.finish(None).unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "* Watching\n\n* Errors Report: \n└──[1] File 'a' does not exist.\n",
+    );
+}
+
+
+#[test]
+fn test_list() {
+    let (mut out, mut err) = (Vec::new(), Vec::new());
+    let mut printer = List::new(&mut out, &mut err, Look::plain(), false, true);
+    printer.file_end("with", Shown { lines: 1, matched: true, ..Shown::default() }).unwrap();
+    printer.file_end("without", Shown::default()).unwrap();
+    assert_eq!(out, b"without\0");
+}
+
+
+#[test]
+fn test_standard_changed() {
+    let matcher = Matcher::new("lenz", false);
+    let look = Look::plain();
+    let mut out = Vec::new();
+    let gutter = Gutter { column: false, byte_offset: true };
+    let mut printer = Standard::new(&mut out, Look::plain(), gutter, false, false, Fit::Full);
+    printer.changed("a.txt", &Cell::new(&matcher, &look, String::from("old lenz"), 2, 0, 0), false).unwrap();
+    printer.changed("a.txt", &Cell::new(&matcher, &look, String::from("new lenz"), 5, 0, 0), true).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), "-2       old lenz\n+5       new lenz\n");
+}
@@ -0,0 +1,78 @@
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Cursor};
+use lenz::range::Selection;
+
+
+const TEXT: &[u8] = b"one\ntwo\nthree\nfour\nfive\n";
+
+
+#[test]
+fn test_parse_lines() {
+    assert_eq!(Selection::parse_lines("12:18"), Some(Selection::Lines { first: 12, last: 18 }));
+    assert_eq!(Selection::parse_lines("12:"), Some(Selection::Lines { first: 12, last: usize::MAX }));
+    assert_eq!(Selection::parse_lines(":18"), Some(Selection::Lines { first: 1, last: 18 }));
+    assert_eq!(Selection::parse_lines("7"), Some(Selection::Lines { first: 7, last: 7 }));
+    assert_eq!(Selection::parse_lines("0:5"), None);
+    assert_eq!(Selection::parse_lines("9:5"), None);
+    assert_eq!(Selection::parse_lines("a:b"), None);
+}
+
+
+#[test]
+fn test_parse_bytes() {
+    assert_eq!(Selection::parse_bytes("100:20"), Some(Selection::Bytes { offset: 100, len: 20 }));
+    assert_eq!(Selection::parse_bytes("100:"), Some(Selection::Bytes { offset: 100, len: u64::MAX }));
+    assert_eq!(Selection::parse_bytes("100"), None);
+}
+
+
+#[test]
+fn test_skip_lines() {
+    let selection = Selection::parse_lines("3:4").unwrap();
+    let mut reader = Cursor::new(TEXT);
+    assert_eq!(selection.skip(&mut reader).unwrap(), (2, 8));
+    let mut line = String::new();
+    reader.read_line(&mut line).unwrap();
+    assert_eq!(line, "three\n");
+}
+
+
+#[test]
+fn test_skip_bytes() {
+    let selection = Selection::parse_bytes("10:3").unwrap();
+    let mut reader = Cursor::new(TEXT);
+    assert_eq!(selection.skip(&mut reader).unwrap(), (2, 10));
+    let mut line = String::new();
+    reader.read_line(&mut line).unwrap();
+    assert_eq!(line, "ree\n");
+}
+
+
+#[test]
+fn test_seek_bytes() {
+    let path = std::env::temp_dir().join(format!("lenz_range_{}", std::process::id()));
+    fs::write(&path, TEXT).unwrap();
+    let mut reader = BufReader::new(File::open(&path).unwrap());
+    assert_eq!(Selection::parse_bytes("10:3").unwrap().seek(&mut reader).unwrap(), (2, 10));
+    let mut line = String::new();
+    reader.read_line(&mut line).unwrap();
+    assert_eq!(line, "ree\n");
+    let mut reader = BufReader::new(File::open(&path).unwrap());
+    assert_eq!(Selection::parse_bytes("100:3").unwrap().seek(&mut reader).unwrap(), (5, 24));
+    let mut reader = BufReader::new(File::open(&path).unwrap());
+    assert_eq!(Selection::parse_lines("3:").unwrap().seek(&mut reader).unwrap(), (2, 8));
+    fs::remove_file(&path).unwrap();
+}
+
+
+#[test]
+fn test_slice() {
+    let (part, before) = Selection::parse_lines("2:3").unwrap().slice(TEXT);
+    assert_eq!((part, before), (&b"two\nthree\n"[..], 1));
+    let (part, before) = Selection::parse_lines("4:").unwrap().slice(TEXT);
+    assert_eq!((part, before), (&b"four\nfive\n"[..], 3));
+    let (part, before) = Selection::parse_bytes("10:6").unwrap().slice(TEXT);
+    assert_eq!((part, before), (&b"ree\nfo"[..], 2));
+    let (part, _) = Selection::parse_bytes("100:6").unwrap().slice(TEXT);
+    assert!(part.is_empty());
+}
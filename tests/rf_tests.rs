@@ -0,0 +1,118 @@
+use lenz::opts::{parse_args, Action, Mode, Options};
+use lenz::cells::{Fit, Gutter};
+use lenz::printer::{Count, Printer, Standard};
+use lenz::style::Look;
+use lenz::rf::{ReadableFile, Shown};
+use lenz::searcher::SearcherBuilder;
+
+
+fn options(args: &[&str]) -> Options {
+    let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+    match parse_args(&args) {
+        Ok(Action::Search(opts)) => *opts,
+        _ => panic!("The arguments should be valid."),
+    }
+}
+
+
+fn temp_file(name: &str, contents: &str) -> String {
+    let path = std::env::temp_dir().join(format!("lenz_rf_{}_{}", std::process::id(), name));
+    std::fs::write(&path, contents).unwrap();
+    path.to_string_lossy().to_string()
+}
+
+
+#[test]
+fn test_list_modes() {
+    assert_eq!(options(&["q", "f", "-l"]).mode, Mode::FilesWithMatches);
+    assert_eq!(options(&["q", "f", "--files-without-match", "-0"]).mode, Mode::FilesWithoutMatch);
+    assert!(options(&["q", "f", "-L", "-0"]).null);
+}
+
+
+#[test]
+fn test_has_match() {
+    let path = temp_file("has_match", "one\ntwo lenz\nthree lenz\n");
+    let opts = options(&["lenz", &path, "-l"]);
+    let look = Look::plain();
+    let searcher = SearcherBuilder::new("lenz").build();
+    for mmap in [false, true] {
+        assert!(ReadableFile::new(path.clone(), &searcher, &opts, &look, mmap).has_match().unwrap());
+    }
+    let other = SearcherBuilder::new("four").build();
+    for mmap in [false, true] {
+        assert!(!ReadableFile::new(path.clone(), &other, &opts, &look, mmap).has_match().unwrap());
+    }
+    std::fs::remove_file(&path).unwrap();
+}
+
+
+#[test]
+fn test_limits() {
+    let path = temp_file("limits", "lenz\nx\nlenz lenz\nlenz\n");
+    let opts = options(&["lenz", &path, "-c", "-m", "2", "--max-total=5"]);
+    let look = Look::plain();
+    assert_eq!((opts.max_count, opts.max_total), (Some(2), Some(5)));
+    let searcher = SearcherBuilder::new("lenz").build();
+    for mmap in [false, true] {
+        let rf = ReadableFile::new(path.clone(), &searcher, &opts, &look, mmap);
+        let mut printer = Count::new(Vec::new(), Look::plain());
+        assert_eq!(rf.search(Some(2), &mut printer).unwrap(), Shown { lines: 2, truncated: true, matched: true, failed: false });
+        assert_eq!(rf.search(Some(3), &mut printer).unwrap(), Shown { lines: 3, truncated: false, matched: true, failed: false });
+    }
+    std::fs::remove_file(&path).unwrap();
+}
+
+
+#[test]
+fn test_unreadable_file() {
+    let path = temp_file("unreadable", "lenz\n");
+    let opts = options(&["lenz", &path, "-c"]);
+    let look = Look::plain();
+    let searcher = SearcherBuilder::new("lenz").build();
+    std::fs::remove_file(&path).unwrap();
+    for mmap in [false, true] {
+        let rf = ReadableFile::new(path.clone(), &searcher, &opts, &look, mmap);
+        assert!(rf.has_match().is_err());
+        let mut out = Vec::new();
+        let mut printer = Count::new(&mut out, Look::plain());
+        assert_eq!(rf.search(None, &mut printer).unwrap(), Shown { failed: true, ..Shown::default() });
+        printer.finish(None).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(!text.contains("[0]"));
+        assert!(text.contains("does not exist"));
+    }
+}
+
+
+#[test]
+fn test_context() {
+    let path = temp_file("context", "a\nb lenz\nc\nd\ne\nf lenz\ng\n");
+    let opts = options(&["lenz", &path, "-C", "1"]);
+    let look = Look::plain();
+    let searcher = SearcherBuilder::new("lenz").build();
+    let rf = ReadableFile::new(path.clone(), &searcher, &opts, &look, false);
+    let mut out = Vec::new();
+    let mut printer = Standard::new(&mut out, Look::plain(), Gutter::default(), false, false, Fit::Full);
+    assert_eq!(rf.search(None, &mut printer).unwrap(), Shown { lines: 2, truncated: false, matched: true, failed: false });
+    let text = String::from_utf8(out).unwrap();
+    let linenos: Vec<&str> = text.lines().skip(3).filter_map(|l| l.split_whitespace().next()).collect();
+    assert_eq!(linenos, vec!["1", "2", "3", "5", "6", "7"]);
+    std::fs::remove_file(&path).unwrap();
+}
+
+
+#[test]
+fn test_lines_with_a_selection() {
+    let path = temp_file("lines", "a
+b lenz
+c
+d lenz
+");
+    let opts = options(&["lenz", &path, "-p", "--lines", "2:3"]);
+    let look = Look::plain();
+    let searcher = SearcherBuilder::new("lenz").selection(opts.selection).build();
+    let rf = ReadableFile::new(path.clone(), &searcher, &opts, &look, false);
+    assert_eq!(rf.matched_lines().unwrap(), vec![(2, String::from("b lenz"))]);
+    std::fs::remove_file(&path).unwrap();
+}
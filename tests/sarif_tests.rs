@@ -0,0 +1,33 @@
+use lenz::cells::Cell;
+use lenz::matcher::Matcher;
+use lenz::sarif;
+use lenz::style::Look;
+
+
+#[test]
+fn test_uri() {
+    assert_eq!(sarif::uri("src/lib.rs"), "src/lib.rs");
+    assert_eq!(sarif::uri("/tmp/a b#1.txt"), "file:///tmp/a%20b%231.txt");
+    assert_eq!(sarif::uri("dir\\file.txt"), "dir/file.txt");
+}
+
+
+#[test]
+fn test_utf16_region() {
+    let matcher = Matcher::new("lenz", false);
+    let look = Look::plain();
+    let cell = Cell::new(&matcher, &look, String::from("😀 lenz"), 2, 0, 1);
+    let results = sarif::results("lenz", "a.txt", &cell);
+    assert_eq!(results.len(), 1);
+    assert!(results[0].contains("\"startLine\":2,\"startColumn\":4,\"endColumn\":8"));
+}
+
+
+#[test]
+fn test_report() {
+    let report = sarif::report("lenz", &[], &[String::from("oops")], &[]);
+    assert!(report.starts_with("{\"$schema\""));
+    assert!(report.contains("\"version\":\"2.1.0\""));
+    assert!(report.contains("\"executionSuccessful\":false"));
+    assert!(report.contains("\"results\":[]"));
+}
@@ -0,0 +1,92 @@
+use std::io::{Error, Read};
+use lenz::range::Selection;
+use lenz::searcher::{CaseMode, Match, SearcherBuilder};
+
+
+#[test]
+fn test_search_reader() {
+    let searcher = SearcherBuilder::new("lenz").build();
+    let found: Vec<Match> = searcher.search_reader("text", "a lenz\nLENZ\r\nlenz lenz\n".as_bytes())
+                                    .collect::<Result<_, _>>()
+                                    .unwrap();
+    assert_eq!(found.len(), 2);
+    assert_eq!(found[0], Match {
+        path: String::from("text"),
+        line_number: 1,
+        offset: 0,
+        line: String::from("a lenz"),
+        spans: vec![(2, 4)],
+    });
+    assert_eq!(found[1].line_number, 3);
+    assert_eq!(found[1].offset, 13);
+    assert_eq!(found[1].spans, vec![(0, 4), (5, 4)]);
+}
+
+
+#[test]
+fn test_search_limits() {
+    let text = "lenz 1\nlenz 2\nlenz 3\nlenz 4\n";
+    let searcher = SearcherBuilder::new("lenz").max_count(Some(2)).build();
+    assert_eq!(searcher.search_reader("text", text.as_bytes()).count(), 2);
+    let searcher = SearcherBuilder::new("lenz").selection(Selection::parse_lines("2:3")).build();
+    let lines: Vec<usize> = searcher.search_reader("text", text.as_bytes())
+                                    .map(|m| m.unwrap().line_number)
+                                    .collect();
+    assert_eq!(lines, vec![2, 3]);
+}
+
+
+#[test]
+fn test_search_passthru() {
+    let searcher = SearcherBuilder::new("lenz").max_count(Some(1)).build();
+    let found: Vec<Match> = searcher.search_reader("text", "a\nlenz\nb\nlenz\n".as_bytes())
+                                    .passthru(true)
+                                    .collect::<Result<_, _>>()
+                                    .unwrap();
+    let lines: Vec<(usize, u64, usize)> = found.iter().map(|m| (m.line_number, m.offset, m.spans.len())).collect();
+    assert_eq!(lines, vec![(1, 0, 0), (2, 2, 1), (3, 7, 0)]);
+}
+
+
+#[test]
+fn test_search_invalid_utf8() {
+    let searcher = SearcherBuilder::new("lenz").build();
+    let found: Vec<Match> = searcher.search_reader("text", &b"a\xff\nlenz \xfe\n"[..])
+                                    .passthru(true)
+                                    .collect::<Result<_, _>>()
+                                    .unwrap();
+    let lines: Vec<&str> = found.iter().map(|m| m.line.as_str()).collect();
+    assert_eq!(lines, vec!["a\u{fffd}", "lenz \u{fffd}"]);
+    assert_eq!(found[1].spans, vec![(0, 4)]);
+}
+
+
+#[test]
+fn test_case_mode() {
+    assert!(!CaseMode::Sensitive.ignore_case("lenz"));
+    assert!(CaseMode::Insensitive.ignore_case("Lenz"));
+    assert!(CaseMode::Smart.ignore_case("lenz"));
+    assert!(!CaseMode::Smart.ignore_case("Lenz"));
+    let searcher = SearcherBuilder::new("lenz").case_mode(CaseMode::Smart).word(true).build();
+    assert_eq!(searcher.search_reader("text", "LENZ\nlenzes\n".as_bytes()).count(), 1);
+}
+
+
+struct Broken;
+
+
+impl Read for Broken {
+    fn read(&mut self, _buf: &mut [u8]) -> Result<usize, Error> {
+        Err(Error::other("broken"))
+    }
+}
+
+
+#[test]
+fn test_search_errors() {
+    let searcher = SearcherBuilder::new("lenz").build();
+    assert!(searcher.search_path("does/not/exist").is_err());
+    let results: Vec<Result<Match, Error>> = searcher.search_reader("broken", Broken).collect();
+    assert_eq!(results.len(), 1);
+    assert!(results[0].is_err());
+}
@@ -1,4 +1,4 @@
-use lenz::style::{hg_query, ANSIStyle};
+use lenz::style::{hg_query, ANSIStyle, Color, ColorDepth};
 
 
 #[test]
@@ -126,4 +126,36 @@ fn test_hg_query_case_insensitivity_no_match() {
     let o = hg_query(query, text, true);
     let e = "Just for testing ...";
     assert_eq!(o, e);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_color_parse() {
+    assert_eq!(Color::parse("magenta"), Some(Color::MAGENTA));
+    assert_eq!(Color::parse("208"), Some(Color::Fixed(208)));
+    assert_eq!(Color::parse("#1E90ff"), Some(Color::Rgb(30, 144, 255)));
+    assert_eq!(Color::Rgb(30, 144, 255).to_string(), "#1e90ff");
+    for bad in ["pink", "256", "#12345", "#12345g", "-1"] {
+        assert_eq!(Color::parse(bad), None, "{}", bad);
+    }
+}
+
+
+#[test]
+fn test_color_downgrade() {
+    assert_eq!(Color::Rgb(255, 135, 0).downgrade(ColorDepth::Ansi256), Color::Fixed(208));
+    assert_eq!(Color::Rgb(128, 128, 128).downgrade(ColorDepth::Ansi256), Color::Fixed(244));
+    assert_eq!(Color::Rgb(250, 10, 10).downgrade(ColorDepth::Basic), Color::RED);
+    assert_eq!(Color::Fixed(12).downgrade(ColorDepth::Basic), Color::BLUE);
+    assert_eq!(Color::Fixed(28).downgrade(ColorDepth::Basic), Color::GREEN);
+    assert_eq!(Color::Rgb(1, 2, 3).downgrade(ColorDepth::TrueColor), Color::Rgb(1, 2, 3));
+    assert_eq!(Color::Fixed(208).sgr(true), "48;5;208");
+}
+
+
+#[test]
+fn test_color_depth_from_env() {
+    assert_eq!(ColorDepth::from_env("truecolor", "xterm"), ColorDepth::TrueColor);
+    assert_eq!(ColorDepth::from_env("", "xterm-direct"), ColorDepth::TrueColor);
+    assert_eq!(ColorDepth::from_env("", "screen-256color"), ColorDepth::Ansi256);
+    assert_eq!(ColorDepth::from_env("", "xterm"), ColorDepth::Basic);
+}
@@ -0,0 +1,59 @@
+use lenz::style::{Color, ColorDepth};
+use lenz::theme::{Change, ColorSpec, Slot, Style, Theme};
+
+
+#[test]
+fn test_color_spec_parse() {
+    let spec = ColorSpec::parse("match:bg:yellow").unwrap();
+    assert_eq!(spec.slot, Slot::Match);
+    assert_eq!(spec.change, Change::Bg(Some(Color::YELLOW)));
+    assert_eq!(ColorSpec::parse("matched-lineno:style:nobold").unwrap().change, Change::Bold(false));
+    assert_eq!(ColorSpec::parse("path:none").unwrap().change, Change::Set(Style::NONE));
+    assert_eq!(ColorSpec::parse("count:fg:none").unwrap().to_string(), "count:fg:none");
+    assert_eq!(ColorSpec::parse("error:style:underline").unwrap().to_string(), "error:style:underline");
+    for bad in ["match", "match:bg", "match:bg:pink", "title:fg:red", "match:style:blink", "match:fg:red:x", "match:bold+blink"] {
+        assert!(ColorSpec::parse(bad).is_none(), "{}", bad);
+    }
+}
+
+
+#[test]
+fn test_theme_apply() {
+    let mut theme = Theme::named("default").unwrap();
+    assert_eq!(theme.style(Slot::Match), Style::bg(Color::RED));
+    theme.apply(&ColorSpec::parse("match:bg:yellow").unwrap());
+    theme.apply(&ColorSpec::parse("match:fg:black").unwrap());
+    theme.apply(&ColorSpec::parse("match:style:bold").unwrap());
+    let style = theme.style(Slot::Match);
+    assert_eq!(style, Style { fg: Some(Color::BLACK), bg: Some(Color::YELLOW), bold: true, ..Style::NONE });
+    theme.apply(&ColorSpec::parse("match:none").unwrap());
+    assert_eq!(theme.style(Slot::Match), Style::NONE);
+    assert!(Theme::named("solarized").is_none());
+    let colorblind = Theme::named("colorblind").unwrap();
+    assert_ne!(colorblind.style(Slot::Removed).fg, Some(Color::RED));
+    assert_ne!(colorblind.style(Slot::Added).fg, Some(Color::GREEN));
+    assert_eq!(ColorSpec::parse("added:fg:cyan").unwrap().slot, Slot::Added);
+}
+
+
+#[test]
+fn test_style_value() {
+    let spec = ColorSpec::parse("match:bold+underline+fg=#ff8800+bg=236").unwrap();
+    let style = Style { fg: Some(Color::Rgb(255, 136, 0)), bg: Some(Color::Fixed(236)), bold: true, underline: true, ..Style::NONE };
+    assert_eq!(spec.change, Change::Set(style));
+    assert_eq!(spec.to_string(), "match:bold+underline+fg=#ff8800+bg=236");
+    assert_eq!(style.sgr(ColorDepth::TrueColor), "1;4;38;2;255;136;0;48;5;236");
+    assert_eq!(style.sgr(ColorDepth::Ansi256), "1;4;38;5;208;48;5;236");
+    assert_eq!(style.sgr(ColorDepth::Basic), "1;4;33;40");
+    assert_eq!(Style::from_sgr("1;4;38;2;255;136;0;48;5;236"), Some(style));
+    assert_eq!(Style::from_sgr("3;37"), Some(Style { fg: Some(Color::WHITE), italic: true, ..Style::NONE }));
+    assert_eq!(Style::from_sgr("38;9"), None);
+}
+
+
+#[test]
+fn test_style_paint() {
+    let style = Style { fg: Some(Color::BLUE), underline: true, ..Style::NONE };
+    assert_eq!(style.paint("12", ColorDepth::TrueColor), "\x1b[4;34m12\x1b[0m");
+    assert_eq!(Style::NONE.paint("12", ColorDepth::TrueColor), "12");
+}
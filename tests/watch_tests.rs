@@ -0,0 +1,46 @@
+use std::env;
+use std::fs;
+use lenz::watch::{diff_matches, expand_paths};
+
+
+fn lines(v: &[(usize, &str)]) -> Vec<(usize, String)> {
+    v.iter().map(|(n, l)| (*n, l.to_string())).collect()
+}
+
+
+#[test]
+fn test_diff_matches_added_and_removed() {
+    let previous = lines(&[(1, "lenz a"), (4, "lenz b"), (9, "lenz c")]);
+    let current = lines(&[(1, "lenz a"), (2, "lenz new"), (10, "lenz c")]);
+    let (added, removed) = diff_matches(&previous, &current);
+    assert_eq!(added, lines(&[(2, "lenz new")]));
+    assert_eq!(removed, lines(&[(4, "lenz b")]));
+}
+
+
+#[test]
+fn test_diff_matches_duplicated_lines() {
+    let previous = lines(&[(1, "lenz"), (2, "lenz")]);
+    let current = lines(&[(1, "lenz"), (2, "lenz"), (3, "lenz")]);
+    let (added, removed) = diff_matches(&previous, &current);
+    assert_eq!(added, lines(&[(3, "lenz")]));
+    assert!(removed.is_empty());
+}
+
+
+#[test]
+fn test_expand_paths() {
+    let dir = env::temp_dir().join(format!("lenz-watch-{}", std::process::id()));
+    fs::create_dir_all(dir.join("sub")).unwrap();
+    fs::write(dir.join("b.txt"), "").unwrap();
+    fs::write(dir.join("a.txt"), "").unwrap();
+    let d = dir.to_string_lossy().to_string();
+    let o = expand_paths(&[d.clone(), String::from("missing.txt")]);
+    let e = vec![
+        dir.join("a.txt").to_string_lossy().to_string(),
+        dir.join("b.txt").to_string_lossy().to_string(),
+        String::from("missing.txt"),
+    ];
+    assert_eq!(o, e);
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,56 @@
+use lenz::width::{char_width, column_at, padding, str_width, Cursor, TAB_WIDTH};
+
+
+#[test]
+fn test_char_width() {
+    assert_eq!(char_width('a'), 1);
+    assert_eq!(char_width('界'), 2);
+    assert_eq!(char_width('😀'), 2);
+    assert_eq!(char_width('\u{0301}'), 0);
+    assert_eq!(char_width('\u{200D}'), 0);
+}
+
+
+#[test]
+fn test_str_width_tabs() {
+    assert_eq!(str_width("\tx"), 9);
+    assert_eq!(str_width("abc\tx"), 9);
+    assert_eq!(str_width("abcdefgh\tx"), 17);
+}
+
+
+#[test]
+fn test_column_at() {
+    let line = "\t世界 lenz";
+    let byte = line.find("lenz").unwrap();
+    assert_eq!(column_at(line, byte, TAB_WIDTH), 14);
+    assert_eq!(column_at("lenz", 0, TAB_WIDTH), 1);
+    assert_eq!(column_at("é lenz", 1, TAB_WIDTH), 1);
+}
+
+
+#[test]
+fn test_str_width_zwj() {
+    // A family and a technologist are each a single emoji, joined with zero-width joiners.
+    assert_eq!(str_width("👨\u{200D}👩\u{200D}👧"), 2);
+    assert_eq!(str_width("👩\u{200D}💻 lenz"), 7);
+}
+
+
+#[test]
+fn test_cursor_tab_width() {
+    let mut cursor = Cursor::new(4);
+    assert_eq!(cursor.advance('a'), 1);
+    assert_eq!(cursor.advance('\t'), 3);
+    assert_eq!(cursor.advance('界'), 2);
+    assert_eq!(cursor.advance('\t'), 2);
+    assert_eq!(cursor.column(), 8);
+}
+
+
+#[test]
+fn test_padding() {
+    assert_eq!(padding("12", 7), "     ");
+    assert_eq!(padding("世界", 7), "   ");
+    assert_eq!(padding("123456789", 7), "");
+}